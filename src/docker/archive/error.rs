@@ -0,0 +1,25 @@
+use std::io;
+use thiserror::Error;
+
+/// Error type for handling `docker save` archive related failures
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Error caused by a tarball that cannot be parsed, such as a truncated one
+    #[error("invalid tar archive: {0}")]
+    InvalidArchive(#[source] io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(
+        Error::InvalidArchive(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated")),
+        "invalid tar archive: truncated";
+        "Invalid archive"
+    )]
+    fn display(error: Error, expected: &str) {
+        assert_eq!(error.to_string(), expected);
+    }
+}
@@ -0,0 +1,59 @@
+//! Reading and writing of the archives produced by `docker save` and consumed by `docker load`.
+
+pub mod error;
+#[cfg(feature = "fs")]
+mod reader;
+mod writer;
+
+#[cfg(feature = "fs")]
+pub use reader::*;
+pub use writer::*;
+
+/// Name of the archive entry holding the image manifest.
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+/// Name of the archive entry holding the repositories data.
+const REPOSITORIES_ENTRY: &str = "repositories";
+
+#[cfg(test)]
+pub(crate) mod tests {
+    #[cfg(feature = "fs")]
+    use crate::docker;
+    #[cfg(feature = "fs")]
+    use std::fs;
+    #[cfg(feature = "fs")]
+    use std::path::{Path, PathBuf};
+
+    /// Appends an entry with the given content to a tarball
+    #[cfg(feature = "fs")]
+    pub(crate) fn append<W: std::io::Write>(
+        builder: &mut tar::Builder<W>,
+        path: &str,
+        content: &[u8],
+    ) {
+        let mut header = tar::Header::new_gnu();
+
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, content)
+            .expect("Append archive entry");
+    }
+
+    /// Creates, inside `dir`, a `docker save` like archive out of the Docker test data
+    #[cfg(feature = "fs")]
+    pub(crate) fn test_archive(dir: &Path) -> PathBuf {
+        let image_dir = dir.join("image");
+        let archive_path = dir.join("image.tar");
+        let mut builder = tar::Builder::new(fs::File::create(&archive_path).expect("Archive"));
+
+        docker::tests::test_image_dir(&image_dir);
+        builder
+            .append_dir_all("", image_dir)
+            .expect("Append image directory");
+        builder.finish().expect("Finish archive");
+
+        archive_path
+    }
+}
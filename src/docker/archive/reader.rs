@@ -0,0 +1,743 @@
+use crate::docker;
+use crate::docker::archive::error::Error;
+use crate::docker::archive::{MANIFEST_ENTRY, REPOSITORIES_ENTRY};
+use crate::docker::distribution::Repositories;
+use crate::docker::image::{self, ImageConfiguration, ImageManifest, ManifestItem};
+use crate::docker::layer::{self, Compression};
+use crate::error::{ParsleyError, ParsleyResult};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+
+/// Location of a file's content inside the archive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct EntryLocation {
+    /// Offset (in bytes) of the file content from the start of the archive.
+    offset: u64,
+    /// Size (in bytes) of the file content.
+    size: u64,
+}
+
+/// Kind of an archive entry, as streamed by [DockerArchive::for_each_entry](
+/// DockerArchive::for_each_entry), along with its path within the archive.
+///
+/// Entries are classified by their path, following the layout of `docker save`, since the
+/// manifest naming the configurations and layers usually comes last.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EntryKind {
+    /// The `manifest.json` file.
+    Manifest,
+    /// The `repositories` file.
+    Repositories,
+    /// A configuration, named `<hex>.json` at the root of the archive.
+    Configuration(String),
+    /// A layer, named `<id>/layer.tar`.
+    Layer(String),
+    /// Any other file (e.g. the legacy `<id>/json` files).
+    Other(String),
+}
+
+impl EntryKind {
+    /// Classifies an entry by its normalized path.
+    fn from_path(path: String) -> Self {
+        match path.as_str() {
+            MANIFEST_ENTRY => Self::Manifest,
+            REPOSITORIES_ENTRY => Self::Repositories,
+            _ if path.ends_with("/layer.tar") => Self::Layer(path),
+            _ if path.ends_with(".json") && !path.contains('/') => Self::Configuration(path),
+            _ => Self::Other(path),
+        }
+    }
+}
+
+/// An archive produced by `docker save`.
+///
+/// The archive is indexed when opened: the manifest, the repositories data and every configuration
+/// referenced by the manifest are parsed eagerly, while the layers are left inside the archive.
+///
+/// Gzip and zstd compressed archives are detected by their magic bytes and decompressed into a
+/// temporary file, deleted along with the archive, since layers cannot be reached without reading
+/// through the whole compressed stream.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::archive::DockerArchive;
+///
+/// let archive = DockerArchive::from_file("postgres.tar").unwrap();
+///
+/// for item in &archive.manifest().0 {
+///     let image_config = archive.configuration(item).unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct DockerArchive {
+    /// Path to the archive on disk.
+    path: PathBuf,
+    /// Temporary file holding the decompressed tarball of a compressed archive, `None` for a plain
+    /// tarball which is read in place.
+    decompressed: Option<Arc<NamedTempFile>>,
+    /// Location of every regular file within the archive, keyed by its path.
+    entries: BTreeMap<String, EntryLocation>,
+    /// The `manifest.json` file of the archive.
+    manifest: ImageManifest,
+    /// The `repositories` file of the archive, if present.
+    repositories: Option<Repositories>,
+    /// Configurations referenced by the manifest, keyed by their path within the archive.
+    configurations: BTreeMap<String, ImageConfiguration>,
+}
+
+impl DockerArchive {
+    /// Attempts to load a `docker save` archive from a file.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the file does not exist or cannot be read,
+    /// or is gzip (resp. zstd) compressed while the `gzip` (resp. `zstd`) feature is disabled
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the file is not a valid, possibly
+    /// compressed, tarball or the archive does not contain a manifest or one of the configurations
+    /// it references
+    /// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if any of the JSON files cannot be
+    /// deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::archive::DockerArchive;
+    ///
+    /// let archive = DockerArchive::from_file("postgres.tar").unwrap();
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let decompressed = Self::decompress(&path)?.map(Arc::new);
+        let file = fs::File::open(
+            decompressed
+                .as_ref()
+                .map_or(path.as_path(), |file| file.path()),
+        )?;
+        let archive_size = file.metadata()?.len();
+        let entries = Self::index(file, archive_size)?;
+        let mut archive = Self {
+            path,
+            decompressed,
+            entries,
+            manifest: ImageManifest::default(),
+            repositories: None,
+            configurations: BTreeMap::default(),
+        };
+
+        archive.manifest = ImageManifest::from_slice(&archive.read_entry(MANIFEST_ENTRY)?.ok_or(
+            docker::Error::from(image::error::Error::MissingImageManifest),
+        )?)?;
+        archive.repositories = archive
+            .read_entry(REPOSITORIES_ENTRY)?
+            .map(|content| Repositories::from_slice(&content))
+            .transpose()?;
+
+        for item in &archive.manifest.0 {
+            let content = archive
+                .read_entry(item.config())?
+                .ok_or(docker::Error::from(
+                    image::error::Error::MissingImageConfiguration,
+                ))?;
+
+            archive.configurations.insert(
+                item.config().to_owned(),
+                ImageConfiguration::from_slice(&content)?,
+            );
+        }
+
+        Ok(archive)
+    }
+
+    /// Streams the regular files of a `docker save` archive, in archive order, to `f`, along with
+    /// a reader over their content, such that each one can be processed and discarded in turn.
+    ///
+    /// Unlike [from_file](DockerArchive::from_file), nothing is held in memory, compressed
+    /// archives included, thus multi-gigabyte archives can be processed. The content left unread
+    /// by `f` is skipped.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the file does not exist or cannot be read,
+    /// or is gzip (resp. zstd) compressed while the `gzip` (resp. `zstd`) feature is disabled
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the file is not a valid, possibly
+    /// compressed, tarball
+    /// Any error returned by `f`, which stops the iteration.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use std::io;
+    /// use parsley::docker::archive::{DockerArchive, EntryKind};
+    ///
+    /// DockerArchive::for_each_entry("postgres.tar", |kind, reader| {
+    ///     if let EntryKind::Layer(path) = kind {
+    ///         println!("{path}: {} bytes", io::copy(reader, &mut io::sink())?);
+    ///     }
+    ///
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn for_each_entry<P, F>(path: P, mut f: F) -> ParsleyResult<()>
+    where
+        P: AsRef<Path>,
+        F: FnMut(EntryKind, &mut dyn Read) -> ParsleyResult<()>,
+    {
+        let file = io::BufReader::new(fs::File::open(path)?);
+        let mut archive = tar::Archive::new(layer::decompressed_reader(file)?);
+
+        for entry in archive.entries().map_err(invalid)? {
+            let mut entry = entry.map_err(invalid)?;
+
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let path = normalize(&entry.path().map_err(invalid)?.to_string_lossy()).to_owned();
+
+            f(EntryKind::from_path(path), &mut entry)?;
+        }
+
+        Ok(())
+    }
+
+    /// The image manifest of the archive.
+    pub fn manifest(&self) -> &ImageManifest {
+        &self.manifest
+    }
+
+    /// The repositories data of the archive, if the archive contains a `repositories` file.
+    pub fn repositories(&self) -> Option<&Repositories> {
+        self.repositories.as_ref()
+    }
+
+    /// Retrieves the configuration referenced by a manifest item.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the configuration is not part of the
+    /// archive.
+    pub fn configuration(&self, item: &ManifestItem) -> ParsleyResult<&ImageConfiguration> {
+        Ok(self
+            .configurations
+            .get(item.config())
+            .ok_or(docker::Error::from(
+                image::error::Error::MissingImageConfiguration,
+            ))?)
+    }
+
+    /// Reads the raw bytes of the configuration referenced by a manifest item, as stored within
+    /// the archive, e.g. for computing its digest or repacking it.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the configuration is not part of the
+    /// archive
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the archive cannot be read.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::archive::DockerArchive;
+    ///
+    /// let archive = DockerArchive::from_file("postgres.tar").unwrap();
+    /// let item = &archive.manifest().0[0];
+    ///
+    /// item.verify_config(&archive.configuration_bytes(item).unwrap()).unwrap();
+    /// ```
+    pub fn configuration_bytes(&self, item: &ManifestItem) -> ParsleyResult<Vec<u8>> {
+        Ok(self.read_entry(item.config())?.ok_or(docker::Error::from(
+            image::error::Error::MissingImageConfiguration,
+        ))?)
+    }
+
+    /// Opens a reader over the raw content of a layer, as listed in
+    /// [ManifestItem::layers](ManifestItem::layers).
+    ///
+    /// The layer is streamed straight out of a plain tarball, without loading it into memory.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the layer is not part of the archive
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the archive cannot be read.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use std::io::Read;
+    /// use parsley::docker::archive::DockerArchive;
+    ///
+    /// let archive = DockerArchive::from_file("postgres.tar").unwrap();
+    ///
+    /// for layer in archive.manifest().0[0].layers() {
+    ///     let mut content = Vec::new();
+    ///     archive.layer_reader(layer).unwrap().read_to_end(&mut content).unwrap();
+    /// }
+    /// ```
+    pub fn layer_reader(&self, layer_path: &str) -> ParsleyResult<impl Read> {
+        Ok(self.entry_reader(layer_path)?.ok_or(docker::Error::from(
+            image::error::Error::MissingLayer(layer_path.to_owned()),
+        ))?)
+    }
+
+    /// Size (in bytes) of a layer, as listed in [ManifestItem::layers](ManifestItem::layers).
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the layer is not part of the archive.
+    pub fn layer_size(&self, layer_path: &str) -> ParsleyResult<u64> {
+        Ok(self
+            .entries
+            .get(normalize(layer_path))
+            .map(|location| location.size)
+            .ok_or(docker::Error::from(image::error::Error::MissingLayer(
+                layer_path.to_owned(),
+            )))?)
+    }
+
+    /// Decompresses the archive into a temporary file if the file is compressed, detecting the
+    /// compression from its magic bytes.
+    fn decompress(path: &Path) -> ParsleyResult<Option<NamedTempFile>> {
+        let mut file = fs::File::open(path)?;
+        let mut magic = Vec::new();
+
+        file.by_ref()
+            .take(layer::MAGIC_LEN as u64)
+            .read_to_end(&mut magic)?;
+
+        if Compression::detect(&magic) == Compression::None {
+            return Ok(None);
+        }
+
+        let mut decompressed = NamedTempFile::new()?;
+        let mut writer = io::BufWriter::new(decompressed.as_file_mut());
+        let mut reader = layer::decompressed_reader(io::Cursor::new(magic).chain(file))?;
+        let mut buffer = vec![0; 64 * 1024];
+
+        // Only failures to read are caused by the archive, failures to write being I/O ones
+        loop {
+            match reader.read(&mut buffer).map_err(invalid)? {
+                0 => break,
+                read => io::Write::write_all(&mut writer, &buffer[..read])?,
+            }
+        }
+
+        io::Write::flush(&mut writer)?;
+        drop(writer);
+
+        Ok(Some(decompressed))
+    }
+
+    /// Builds the location index of all regular files within the archive.
+    fn index<R: Read + Seek>(
+        reader: R,
+        archive_size: u64,
+    ) -> ParsleyResult<BTreeMap<String, EntryLocation>> {
+        let mut archive = tar::Archive::new(reader);
+        let mut entries = BTreeMap::new();
+
+        for entry in archive.entries_with_seek().map_err(invalid)? {
+            let entry = entry.map_err(invalid)?;
+
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let path = normalize(&entry.path().map_err(invalid)?.to_string_lossy()).to_owned();
+            let location = EntryLocation {
+                offset: entry.raw_file_position(),
+                size: entry.size(),
+            };
+
+            // Seeking past the content of an entry never fails, thus a truncated content is only
+            // noticed by comparing it against the size of the archive
+            if location.offset + location.size > archive_size {
+                return Err(invalid(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("entry {path} is truncated"),
+                )));
+            }
+
+            entries.insert(path, location);
+        }
+
+        Ok(entries)
+    }
+
+    /// Opens a reader over the content of an archive entry, if the entry exists.
+    fn entry_reader(&self, path: &str) -> ParsleyResult<Option<impl Read>> {
+        let location = match self.entries.get(normalize(path)) {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+        let mut file = fs::File::open(self.tarball_path())?;
+
+        file.seek(SeekFrom::Start(location.offset))?;
+
+        Ok(Some(file.take(location.size)))
+    }
+
+    /// Path of the plain tarball: the decompressed temporary file of a compressed archive, the
+    /// archive itself otherwise.
+    fn tarball_path(&self) -> &Path {
+        self.decompressed
+            .as_ref()
+            .map_or(self.path.as_path(), |file| file.path())
+    }
+
+    /// Reads the full content of an archive entry, if the entry exists.
+    fn read_entry(&self, path: &str) -> ParsleyResult<Option<Vec<u8>>> {
+        self.entry_reader(path)?
+            .map(|mut reader| {
+                let mut content = Vec::new();
+                reader.read_to_end(&mut content)?;
+
+                Ok(content)
+            })
+            .transpose()
+    }
+}
+
+/// Reports a tar parsing failure as an invalid archive.
+fn invalid(err: io::Error) -> ParsleyError {
+    docker::Error::from(Error::InvalidArchive(err)).into()
+}
+
+/// Strips the leading `./` some tools prepend to archive entry paths.
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches("./")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::archive;
+    use test_case::test_case;
+
+    #[test]
+    fn from_file() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let archive = DockerArchive::from_file(archive_path).expect("Could not read archive");
+
+        assert_eq!(
+            archive.manifest(),
+            &ImageManifest::from_file(docker::tests::test_data_path("manifest.json"))
+                .expect("Manifest fixture"),
+            "Archive manifest does not match expected one"
+        );
+        assert_eq!(
+            archive.repositories(),
+            Some(
+                &Repositories::from_file(docker::tests::test_data_path("repositories.json"))
+                    .expect("Repositories fixture")
+            ),
+            "Archive repositories do not match expected ones"
+        );
+        assert_eq!(
+            archive
+                .configuration(&archive.manifest().0[0])
+                .expect("Missing configuration"),
+            &ImageConfiguration::from_file(docker::tests::test_data_path("config.json"))
+                .expect("Config fixture"),
+            "Archive configuration does not match expected one"
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    fn gzip_compress(content: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+
+        io::Write::write_all(&mut encoder, content).expect("Could not compress");
+        encoder.finish().expect("Could not compress")
+    }
+
+    #[cfg(feature = "zstd")]
+    fn zstd_compress(content: &[u8]) -> Vec<u8> {
+        zstd::stream::encode_all(content, 0).expect("Could not compress")
+    }
+
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    #[cfg_attr(feature = "gzip", test_case(gzip_compress, "image.tar.gz"; "Gzip"))]
+    #[cfg_attr(feature = "zstd", test_case(zstd_compress, "image.tar.zst"; "Zstd"))]
+    fn compressed_archive(compress: fn(&[u8]) -> Vec<u8>, file_name: &str) {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let compressed_path = dir.path().join(file_name);
+
+        fs::write(
+            &compressed_path,
+            compress(&fs::read(&archive_path).expect("Could not read archive")),
+        )
+        .expect("Could not write compressed archive");
+
+        let archive = DockerArchive::from_file(archive_path).expect("Could not read archive");
+        let compressed_archive =
+            DockerArchive::from_file(compressed_path).expect("Could not read compressed archive");
+
+        assert_eq!(
+            compressed_archive.manifest(),
+            archive.manifest(),
+            "Compressed archive manifest differs"
+        );
+        assert_eq!(
+            compressed_archive.repositories(),
+            archive.repositories(),
+            "Compressed archive repositories differ"
+        );
+        assert_eq!(
+            compressed_archive.configurations, archive.configurations,
+            "Compressed archive configurations differ"
+        );
+
+        for layer in archive.manifest().0[0].layers() {
+            let mut content = Vec::new();
+
+            compressed_archive
+                .layer_reader(layer)
+                .expect("Missing layer")
+                .read_to_end(&mut content)
+                .expect("Could not read layer");
+
+            assert_eq!(
+                content,
+                docker::tests::layer_content(layer),
+                "Layer {layer} content does not match expected one"
+            );
+        }
+
+        let tarball_path = compressed_archive.tarball_path().to_path_buf();
+
+        assert!(tarball_path.is_file(), "Archive was not decompressed");
+        drop(compressed_archive);
+        assert!(
+            !tarball_path.exists(),
+            "Decompressed archive outlived the archive"
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn truncated_compressed_archive() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let compressed = gzip_compress(&fs::read(&archive_path).expect("Could not read archive"));
+
+        fs::write(&archive_path, &compressed[..compressed.len() / 2])
+            .expect("Could not write compressed archive");
+
+        let result = DockerArchive::from_file(archive_path);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ArchiveError(
+                    Error::InvalidArchive(_)
+                )))
+            ),
+            "Truncated compressed archive was not reported as invalid: {result:?}"
+        );
+    }
+
+    #[test_case(|content| content.to_vec(), "image.tar"; "Plain")]
+    #[cfg_attr(feature = "gzip", test_case(gzip_compress, "image.tar.gz"; "Gzip"))]
+    fn for_each_entry(compress: fn(&[u8]) -> Vec<u8>, file_name: &str) {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let streamed_path = dir.path().join(format!("streamed-{file_name}"));
+        let mut layers = Vec::new();
+        let mut kinds = Vec::new();
+
+        fs::write(
+            &streamed_path,
+            compress(&fs::read(&archive_path).expect("Could not read archive")),
+        )
+        .expect("Could not write archive");
+
+        DockerArchive::for_each_entry(&streamed_path, |kind, reader| {
+            if let EntryKind::Layer(path) = &kind {
+                let mut content = Vec::new();
+
+                reader.read_to_end(&mut content)?;
+                layers.push((path.clone(), content));
+            }
+
+            kinds.push(kind);
+
+            Ok(())
+        })
+        .expect("Could not stream archive");
+
+        let manifest = ImageManifest::from_file(docker::tests::test_data_path("manifest.json"))
+            .expect("Manifest fixture");
+
+        layers.sort();
+
+        assert_eq!(
+            layers,
+            manifest.0[0]
+                .layers()
+                .iter()
+                .map(|layer| (layer.clone(), docker::tests::layer_content(layer)))
+                .collect::<Vec<_>>()
+        );
+        assert!(kinds.contains(&EntryKind::Manifest));
+        assert!(kinds.contains(&EntryKind::Repositories));
+        assert!(kinds.contains(&EntryKind::Configuration(
+            docker::tests::CONFIG_PATH.to_owned()
+        )));
+    }
+
+    #[test]
+    fn for_each_entry_stops_on_error() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let mut count = 0;
+
+        let result = DockerArchive::for_each_entry(archive_path, |_, _| {
+            count += 1;
+
+            Err(ParsleyError::Other("stop".to_owned()))
+        });
+
+        assert!(
+            matches!(result, Err(ParsleyError::Other(ref message)) if message == "stop"),
+            "Callback error was not returned: {result:?}"
+        );
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn configuration_bytes() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let archive = DockerArchive::from_file(archive_path).expect("Could not read archive");
+
+        assert_eq!(
+            archive
+                .configuration_bytes(&archive.manifest().0[0])
+                .expect("Missing configuration"),
+            fs::read(docker::tests::test_data_path("config.json")).expect("Config fixture"),
+            "Configuration bytes differ from the stored ones"
+        );
+
+        let item = image::ManifestItemBuilder::default()
+            .config("missing.json")
+            .build()
+            .expect("Build manifest item");
+        let result = archive.configuration_bytes(&item);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    image::error::Error::MissingImageConfiguration
+                )))
+            ),
+            "Missing configuration did not fail accordingly: {result:?}"
+        );
+    }
+
+    #[test]
+    fn layer_reader() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let archive = DockerArchive::from_file(archive_path).expect("Could not read archive");
+
+        for layer in archive.manifest().0[0].layers() {
+            let mut content = Vec::new();
+
+            archive
+                .layer_reader(layer)
+                .expect("Missing layer")
+                .read_to_end(&mut content)
+                .expect("Could not read layer");
+
+            assert_eq!(
+                content,
+                docker::tests::layer_content(layer),
+                "Layer {layer} content does not match expected one"
+            );
+        }
+    }
+
+    #[test]
+    fn layer_size() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let archive = DockerArchive::from_file(archive_path).expect("Could not read archive");
+
+        for layer in archive.manifest().0[0].layers() {
+            assert_eq!(
+                archive.layer_size(layer).expect("Missing layer"),
+                docker::tests::layer_content(layer).len() as u64,
+                "Layer {layer} size does not match expected one"
+            );
+        }
+        assert!(archive.layer_size("missing/layer.tar").is_err());
+    }
+
+    #[test]
+    fn missing_layer() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let archive = DockerArchive::from_file(archive_path).expect("Could not read archive");
+        let result = archive.layer_reader("missing/layer.tar");
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    image::error::Error::MissingLayer(ref layer)
+                ))) if layer == "missing/layer.tar"
+            ),
+            "Missing layer did not fail accordingly"
+        );
+    }
+
+    #[test]
+    fn missing_manifest() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = dir.path().join("image.tar");
+        let mut builder =
+            tar::Builder::new(fs::File::create(&archive_path).expect("Could not create archive"));
+
+        archive::tests::append(&mut builder, "repositories", b"{}");
+        builder.finish().expect("Could not finish archive");
+
+        let result = DockerArchive::from_file(archive_path);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    image::error::Error::MissingImageManifest
+                )))
+            ),
+            "Archive without manifest did not fail accordingly: {result:?}"
+        );
+    }
+
+    /// Truncates the test archive to the given fraction of its size
+    #[test_case(0.1; "Within content")]
+    #[test_case(0.5; "Middle")]
+    #[test_case(0.9; "Near end")]
+    fn truncated_archive(fraction: f64) {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive_path = archive::tests::test_archive(dir.path());
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .open(&archive_path)
+            .expect("Could not open archive");
+        let size = file.metadata().expect("Could not stat archive").len();
+
+        file.set_len((size as f64 * fraction) as u64)
+            .expect("Could not truncate archive");
+
+        let result = DockerArchive::from_file(archive_path);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ArchiveError(
+                    Error::InvalidArchive(_)
+                )))
+            ),
+            "Truncated archive was not reported as invalid: {result:?}"
+        );
+    }
+}
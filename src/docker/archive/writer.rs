@@ -0,0 +1,330 @@
+use crate::docker;
+use crate::docker::archive::{MANIFEST_ENTRY, REPOSITORIES_ENTRY};
+use crate::docker::distribution::Repositories;
+#[cfg(doc)]
+use crate::docker::image::ImageConfiguration;
+use crate::docker::image::{self, ImageManifest};
+use crate::error::ParsleyResult;
+use std::collections::BTreeSet;
+use std::io::{self, Read, Write};
+
+/// Writer of archives loadable by `docker load`, the counterpart of
+/// [DockerArchive](crate::docker::archive::DockerArchive).
+///
+/// Configurations and layers are appended at the paths the manifest refers to them by, while the
+/// manifest itself is written last, once every file it references has been appended.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::archive::{ArchiveWriter, DockerArchive};
+///
+/// let archive = DockerArchive::from_file("postgres.tar").unwrap();
+/// let mut writer = ArchiveWriter::new(std::fs::File::create("repacked.tar").unwrap());
+///
+/// for item in &archive.manifest().0 {
+///     writer
+///         .append_configuration_bytes(item.config(), &archive.configuration_bytes(item).unwrap())
+///         .unwrap();
+///
+///     for layer in item.layers() {
+///         let size = archive.layer_size(layer).unwrap();
+///         writer
+///             .append_layer(layer, size, archive.layer_reader(layer).unwrap())
+///             .unwrap();
+///     }
+/// }
+///
+/// writer.finish(archive.manifest()).unwrap();
+/// ```
+pub struct ArchiveWriter<W: Write> {
+    /// Tarball being written.
+    builder: tar::Builder<W>,
+    /// Paths of the entries appended so far.
+    entries: BTreeSet<String>,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Creates a writer emitting the archive into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            builder: tar::Builder::new(writer),
+            entries: BTreeSet::default(),
+        }
+    }
+
+    /// Appends the raw bytes of a configuration at the given path (e.g. `<hash>.json`).
+    ///
+    /// The bytes are written as is, since the path and the image ids recorded in `repositories`
+    /// are the digest of the configuration blob: repacking a configuration read out of an archive
+    /// must thus go through [DockerArchive::configuration_bytes](
+    /// crate::docker::archive::DockerArchive::configuration_bytes), while a new configuration is to
+    /// be serialized first and appended at the path named after its [digest](
+    /// crate::docker::image::ImageConfiguration::digest_of_slice).
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the archive cannot be written.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::archive::ArchiveWriter;
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let mut content = Vec::new();
+    /// image_config.to_writer(&mut content).unwrap();
+    ///
+    /// let digest = image::ImageConfiguration::digest_of_slice(&content);
+    /// let path = format!("{}.json", digest.trim_start_matches("sha256:"));
+    /// let mut writer = ArchiveWriter::new(std::fs::File::create("image.tar").unwrap());
+    ///
+    /// writer.append_configuration_bytes(&path, &content).unwrap();
+    /// ```
+    pub fn append_configuration_bytes(&mut self, path: &str, content: &[u8]) -> ParsleyResult<()> {
+        self.append(path, content.len() as u64, content)
+    }
+
+    /// Appends a layer at the given path (e.g. `<id>/layer.tar`), streaming `size` bytes out of
+    /// `layer`. Bytes past `size` are not read.
+    ///
+    /// Since the entry header is written ahead of the layer, the archive is left corrupt when the
+    /// layer turns out to be shorter than `size`.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the layer cannot be read, is shorter than
+    /// `size` or the archive cannot be written.
+    pub fn append_layer<R: Read>(&mut self, path: &str, size: u64, layer: R) -> ParsleyResult<()> {
+        self.append(path, size, layer)
+    }
+
+    /// Appends the `repositories` file, binding the repositories and tags to the images.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the archive cannot be written
+    /// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if the repositories cannot be
+    /// serialized.
+    pub fn append_repositories(&mut self, repositories: &Repositories) -> ParsleyResult<()> {
+        let mut content = Vec::new();
+
+        repositories.to_writer(&mut content)?;
+        self.append(REPOSITORIES_ENTRY, content.len() as u64, content.as_slice())
+    }
+
+    /// Writes the manifest and completes the archive, returning the underlying writer.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if a configuration or a layer
+    /// referenced by the manifest was not appended
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the archive cannot be written
+    /// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if the manifest cannot be serialized.
+    pub fn finish(mut self, manifest: &ImageManifest) -> ParsleyResult<W> {
+        for item in manifest.iter() {
+            if !self.entries.contains(item.config()) {
+                return Err(
+                    docker::Error::from(image::error::Error::MissingImageConfiguration).into(),
+                );
+            }
+
+            if let Some(layer) = item
+                .layers()
+                .iter()
+                .find(|layer| !self.entries.contains(layer.as_str()))
+            {
+                return Err(
+                    docker::Error::from(image::error::Error::MissingLayer(layer.clone())).into(),
+                );
+            }
+        }
+
+        let mut content = Vec::new();
+
+        manifest.to_writer(&mut content)?;
+        self.append(MANIFEST_ENTRY, content.len() as u64, content.as_slice())?;
+
+        Ok(self.builder.into_inner()?)
+    }
+
+    /// Appends a regular file entry of `size` bytes to the archive.
+    ///
+    /// The builder copies the content as is, thus the content is capped to `size` and checked to
+    /// reach it, as the header would otherwise not match the entry.
+    fn append<R: Read>(&mut self, path: &str, size: u64, content: R) -> ParsleyResult<()> {
+        let mut header = tar::Header::new_gnu();
+        let mut content = content.take(size);
+
+        header.set_size(size);
+        header.set_mode(0o644);
+        self.builder.append_data(&mut header, path, &mut content)?;
+
+        if content.limit() > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "entry {path} holds {} bytes instead of {size}",
+                    size - content.limit()
+                ),
+            )
+            .into());
+        }
+
+        self.entries.insert(path.to_owned());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "fs")]
+    use crate::docker::archive::{self, DockerArchive};
+    use crate::error::ParsleyError;
+    #[cfg(feature = "fs")]
+    use std::fs;
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn repack() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let archive = DockerArchive::from_file(archive::tests::test_archive(dir.path()))
+            .expect("Could not read archive");
+        let repacked_path = dir.path().join("repacked.tar");
+        let mut writer =
+            ArchiveWriter::new(fs::File::create(&repacked_path).expect("Could not create archive"));
+
+        for item in &archive.manifest().0 {
+            writer
+                .append_configuration_bytes(
+                    item.config(),
+                    &archive
+                        .configuration_bytes(item)
+                        .expect("Missing configuration"),
+                )
+                .expect("Could not append configuration");
+
+            for layer in item.layers() {
+                writer
+                    .append_layer(
+                        layer,
+                        archive.layer_size(layer).expect("Missing layer"),
+                        archive.layer_reader(layer).expect("Missing layer"),
+                    )
+                    .expect("Could not append layer");
+            }
+        }
+
+        writer
+            .append_repositories(archive.repositories().expect("Missing repositories"))
+            .expect("Could not append repositories");
+        writer
+            .finish(archive.manifest())
+            .expect("Could not finish archive");
+
+        let repacked = DockerArchive::from_file(repacked_path).expect("Could not read repacked");
+
+        assert_eq!(
+            repacked.manifest(),
+            archive.manifest(),
+            "Repacked manifest differs"
+        );
+        assert_eq!(
+            repacked.repositories(),
+            archive.repositories(),
+            "Repacked repositories differ"
+        );
+
+        for item in &archive.manifest().0 {
+            assert_eq!(
+                repacked
+                    .configuration_bytes(item)
+                    .expect("Missing configuration"),
+                fs::read(docker::tests::test_data_path("config.json")).expect("Config fixture"),
+                "Repacked configuration differs from the original bytes"
+            );
+            assert_eq!(
+                repacked.configuration(item).expect("Missing configuration"),
+                archive.configuration(item).expect("Missing configuration"),
+                "Repacked configuration differs"
+            );
+
+            for layer in item.layers() {
+                let mut content = Vec::new();
+
+                repacked
+                    .layer_reader(layer)
+                    .expect("Missing layer")
+                    .read_to_end(&mut content)
+                    .expect("Could not read layer");
+
+                assert_eq!(
+                    content,
+                    docker::tests::layer_content(layer),
+                    "Repacked layer {layer} differs"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn short_layer() {
+        let mut writer = ArchiveWriter::new(Vec::new());
+        let result = writer.append_layer("3b05311756d9/layer.tar", 10, b"abc".as_slice());
+
+        assert!(
+            matches!(result, Err(ParsleyError::Io(ref err)) if err.kind() == io::ErrorKind::UnexpectedEof),
+            "Layer shorter than its size was not rejected: {result:?}"
+        );
+    }
+
+    #[test]
+    fn long_layer() {
+        let mut writer = ArchiveWriter::new(Vec::new());
+
+        writer
+            .append_layer("3b05311756d9/layer.tar", 3, b"abcdef".as_slice())
+            .expect("Could not append layer");
+
+        let content = writer
+            .builder
+            .into_inner()
+            .expect("Could not complete archive");
+        let mut archive = tar::Archive::new(content.as_slice());
+        let mut entry = archive
+            .entries()
+            .expect("Could not read archive")
+            .next()
+            .expect("Missing layer")
+            .expect("Could not read layer");
+        let mut layer = Vec::new();
+
+        entry.read_to_end(&mut layer).expect("Could not read layer");
+
+        assert_eq!(layer, b"abc", "Layer was not capped to its size");
+    }
+
+    #[test]
+    fn missing_layer() {
+        let item = image::ManifestItemBuilder::default()
+            .config("config.json")
+            .layers(vec!["3b05311756d9/layer.tar".to_owned()])
+            .build()
+            .expect("Build manifest item");
+        let mut writer = ArchiveWriter::new(Vec::new());
+
+        writer
+            .append_configuration_bytes(item.config(), b"{}")
+            .expect("Could not append configuration");
+
+        let result = writer.finish(&ImageManifest::from_iter([item.clone()]));
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    image::error::Error::MissingLayer(ref layer)
+                ))) if layer == &item.layers()[0]
+            ),
+            "Archive without layers was not rejected: {:?}",
+            result.map(|_| ())
+        );
+    }
+}
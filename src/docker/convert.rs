@@ -0,0 +1,561 @@
+//! Conversions from the legacy `docker save` format to the OCI image format.
+
+use crate::docker;
+use crate::docker::digest::Digest;
+use crate::docker::image::error::Error;
+use crate::docker::image::{ImageConfiguration, ImageManifest, ManifestItem};
+use crate::docker::media_type::MediaType;
+#[cfg(feature = "fs")]
+use crate::error::ParsleyError;
+use crate::error::ParsleyResult;
+#[cfg(feature = "fs")]
+use crate::util;
+use getset::Getters;
+use oci_spec::image::{
+    Descriptor, DescriptorBuilder, ImageIndex, ImageIndexBuilder, ImageManifestBuilder,
+};
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::{fs, io, path::Path};
+
+/// Annotation holding the full reference of an image.
+const IMAGE_NAME_ANNOTATION: &str = "io.containerd.image.name";
+
+/// Annotation holding the tag of an image.
+const REF_NAME_ANNOTATION: &str = "org.opencontainers.image.ref.name";
+
+/// The blobs of a manifest item, as stored within a `docker save` artifact, out of which the
+/// descriptors of its OCI image manifest are computed.
+///
+/// The descriptors must reference the blobs as stored, thus the configuration is kept as raw bytes,
+/// rather than deserialized, and the layer sizes are those of the layer tarballs.
+#[derive(Getters, Clone, Debug, Default, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct ItemBlobs {
+    /// Raw bytes of the configuration blob.
+    config: Vec<u8>,
+    /// Sizes in bytes of the layer tarballs, in the order of the layers of the item.
+    layer_sizes: Vec<u64>,
+}
+
+impl ItemBlobs {
+    /// Creates the blobs of a manifest item out of the raw bytes of its configuration and the
+    /// sizes of its layer tarballs, in order.
+    pub fn new(config: Vec<u8>, layer_sizes: Vec<u64>) -> Self {
+        Self {
+            config,
+            layer_sizes,
+        }
+    }
+
+    /// Reads the configuration and the layer sizes of `item` from an unpacked `docker save`
+    /// directory.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the configuration or a layer does not
+    /// exist
+    /// [ParsleyError::Io](crate::ParsleyError::Io) if the configuration or a layer cannot be read.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::convert::ItemBlobs;
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("postgres/manifest.json").unwrap();
+    /// let blobs = ItemBlobs::from_dir(&image_manifest.0[0], "postgres").unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn from_dir<P: AsRef<Path>>(item: &ManifestItem, base_dir: P) -> ParsleyResult<Self> {
+        let base_dir = base_dir.as_ref();
+        let config = fs::read(util::path::join_archive_path(base_dir, item.config()))
+            .map_err(|err| missing(err, Error::MissingImageConfiguration))?;
+        let layer_sizes = item
+            .layers()
+            .iter()
+            .map(|layer| {
+                fs::metadata(util::path::join_archive_path(base_dir, layer))
+                    .map(|metadata| metadata.len())
+                    .map_err(|err| missing(err, Error::MissingLayer(layer.clone())))
+            })
+            .collect::<ParsleyResult<Vec<_>>>()?;
+
+        Ok(Self::new(config, layer_sizes))
+    }
+}
+
+/// Converts a legacy image manifest into an OCI image index, where every item of the manifest
+/// becomes the descriptor of an image manifest.
+///
+/// `blobs` holds the blobs of every item of the manifest, in the same order. The digest of every
+/// image manifest is computed over its JSON serialization, as produced by
+/// [manifest_item_to_image_manifest](manifest_item_to_image_manifest).
+///
+/// # Errors
+/// [ParsleyError::Docker](crate::ParsleyError::Docker) if the number of blobs differs from the
+/// number of manifest items or the number of layers of an item differs from the number of
+/// `diff_ids` of its configuration or from the number of its layer sizes
+/// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if a configuration cannot be deserialized or a
+/// manifest cannot be serialized.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::convert::{self, ItemBlobs};
+/// use parsley::docker::image;
+///
+/// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+/// let blobs = image_manifest
+///     .0
+///     .iter()
+///     .map(|item| ItemBlobs::from_dir(item, ".").unwrap())
+///     .collect::<Vec<_>>();
+/// let index = convert::manifest_to_index(&image_manifest, &blobs).unwrap();
+/// ```
+pub fn manifest_to_index(
+    manifest: &ImageManifest,
+    blobs: &[ItemBlobs],
+) -> ParsleyResult<ImageIndex> {
+    if manifest.0.len() != blobs.len() {
+        return Err(docker::Error::from(Error::ConfigurationCountMismatch {
+            expected: manifest.0.len(),
+            actual: blobs.len(),
+        })
+        .into());
+    }
+
+    let manifests = manifest
+        .0
+        .iter()
+        .zip(blobs)
+        .map(|(item, blobs)| {
+            let config = ImageConfiguration::from_slice(blobs.config())?;
+            let image_manifest = serde_json::to_vec(&image_manifest(item, &config, blobs)?)?;
+            let mut descriptor = descriptor(MediaType::DockerManifest, &image_manifest)?;
+
+            descriptor.set_platform(Some(config.platform()));
+            descriptor.set_annotations(item.repo_tags().first().map(|repo_tag| {
+                HashMap::from_iter([
+                    (IMAGE_NAME_ANNOTATION.to_owned(), repo_tag.clone()),
+                    (
+                        REF_NAME_ANNOTATION.to_owned(),
+                        repo_tag
+                            .rsplit_once(':')
+                            .map_or(repo_tag.as_str(), |(_, tag)| tag)
+                            .to_owned(),
+                    ),
+                ])
+            }));
+
+            Ok(descriptor)
+        })
+        .collect::<ParsleyResult<Vec<_>>>()?;
+
+    Ok(ImageIndexBuilder::default()
+        .schema_version(2_u32)
+        .media_type(MediaType::DockerManifestList)
+        .manifests(manifests)
+        .build()?)
+}
+
+/// Converts a legacy manifest item into an OCI image manifest.
+///
+/// The configuration descriptor is computed over the raw configuration of `blobs`. Since the
+/// layers of a `docker save` artifact are uncompressed, their digests are the `diff_ids` of the
+/// configuration and their sizes the layer sizes of `blobs`. Foreign layers, those recorded in the
+/// `layer_sources` of the item, are described by their recorded descriptor instead, referencing
+/// the compressed blob to be pulled from its URLs.
+///
+/// # Errors
+/// [ParsleyError::Docker](crate::ParsleyError::Docker) if the number of layers of the item differs
+/// from the number of `diff_ids` of the configuration or from the number of layer sizes
+/// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if the configuration cannot be deserialized.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::convert::{self, ItemBlobs};
+/// use parsley::docker::image;
+///
+/// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+/// let blobs = ItemBlobs::from_dir(&image_manifest.0[0], ".").unwrap();
+/// let oci_manifest =
+///     convert::manifest_item_to_image_manifest(&image_manifest.0[0], &blobs).unwrap();
+/// ```
+pub fn manifest_item_to_image_manifest(
+    item: &ManifestItem,
+    blobs: &ItemBlobs,
+) -> ParsleyResult<oci_spec::image::ImageManifest> {
+    image_manifest(
+        item,
+        &ImageConfiguration::from_slice(blobs.config())?,
+        blobs,
+    )
+}
+
+/// Converts a legacy manifest item into the image manifest expected by the `oci-distribution`
+/// crate, such that the image can be pushed to a registry.
+///
+/// The manifest holds the same descriptors as the one produced by
+/// [manifest_item_to_image_manifest](manifest_item_to_image_manifest), thus the configuration and
+/// layers are to be pushed as stored.
+///
+/// # Errors
+/// [ParsleyError::Docker](crate::ParsleyError::Docker) if the number of layers of the item differs
+/// from the number of `diff_ids` of the configuration or from the number of layer sizes
+/// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if the configuration cannot be deserialized.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::convert::{self, ItemBlobs};
+/// use parsley::docker::image;
+///
+/// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+/// let blobs = ItemBlobs::from_dir(&image_manifest.0[0], ".").unwrap();
+/// let oci_manifest =
+///     convert::manifest_item_to_oci_distribution_manifest(&image_manifest.0[0], &blobs).unwrap();
+/// ```
+#[cfg(feature = "oci-distribution")]
+pub fn manifest_item_to_oci_distribution_manifest(
+    item: &ManifestItem,
+    blobs: &ItemBlobs,
+) -> ParsleyResult<oci_distribution::manifest::OciImageManifest> {
+    let image_manifest = manifest_item_to_image_manifest(item, blobs)?;
+
+    Ok(oci_distribution::manifest::OciImageManifest {
+        schema_version: 2,
+        media_type: image_manifest
+            .media_type()
+            .as_ref()
+            .map(ToString::to_string),
+        config: oci_distribution_descriptor(image_manifest.config()),
+        layers: image_manifest
+            .layers()
+            .iter()
+            .map(oci_distribution_descriptor)
+            .collect(),
+        artifact_type: None,
+        annotations: image_manifest.annotations().clone(),
+    })
+}
+
+/// Converts a descriptor into its `oci-distribution` counterpart.
+#[cfg(feature = "oci-distribution")]
+fn oci_distribution_descriptor(
+    descriptor: &Descriptor,
+) -> oci_distribution::manifest::OciDescriptor {
+    oci_distribution::manifest::OciDescriptor {
+        media_type: descriptor.media_type().to_string(),
+        digest: descriptor.digest().clone(),
+        size: descriptor.size(),
+        urls: descriptor.urls().clone(),
+        annotations: descriptor.annotations().clone(),
+    }
+}
+
+/// Builds the OCI image manifest of an item, out of its already deserialized configuration.
+fn image_manifest(
+    item: &ManifestItem,
+    config: &ImageConfiguration,
+    blobs: &ItemBlobs,
+) -> ParsleyResult<oci_spec::image::ImageManifest> {
+    config.assert_layer_count(item)?;
+
+    if blobs.layer_sizes().len() != item.layer_count() {
+        return Err(docker::Error::from(Error::LayerCountMismatch {
+            expected: item.layer_count(),
+            actual: blobs.layer_sizes().len(),
+        })
+        .into());
+    }
+
+    let layers = config
+        .oci_spec()
+        .rootfs()
+        .diff_ids()
+        .iter()
+        .zip(blobs.layer_sizes())
+        .map(|(diff_id, size)| match item.layer_source(diff_id) {
+            Some(source) => Ok(source.clone()),
+            None => Ok(DescriptorBuilder::default()
+                .media_type(MediaType::DockerLayer)
+                .digest(diff_id.clone())
+                .size(*size as i64)
+                .build()?),
+        })
+        .collect::<ParsleyResult<Vec<_>>>()?;
+
+    Ok(ImageManifestBuilder::default()
+        .schema_version(2_u32)
+        .media_type(MediaType::DockerManifest)
+        .config(descriptor(MediaType::DockerConfig, blobs.config())?)
+        .layers(layers)
+        .build()?)
+}
+
+/// Creates the descriptor of a content.
+fn descriptor(media_type: MediaType, content: &[u8]) -> ParsleyResult<Descriptor> {
+    Ok(DescriptorBuilder::default()
+        .media_type(media_type)
+        .digest(Digest::sha256(content).to_string())
+        .size(content.len() as i64)
+        .build()?)
+}
+
+/// Maps a file that does not exist to `error`, keeping any other I/O error as is.
+#[cfg(feature = "fs")]
+fn missing(err: io::Error, error: Error) -> ParsleyError {
+    match err.kind() {
+        io::ErrorKind::NotFound => docker::Error::from(error).into(),
+        _ => err.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::image::ManifestItemBuilder;
+    use crate::error::ParsleyError;
+    #[cfg(feature = "fs")]
+    use std::collections::BTreeMap;
+
+    #[cfg(feature = "fs")]
+    fn fixtures() -> (ImageManifest, ItemBlobs) {
+        let manifest = ImageManifest::from_file(docker::tests::test_data_path("manifest.json"))
+            .expect("Manifest fixture");
+        let blobs = ItemBlobs::new(
+            fs::read(docker::tests::test_data_path("config.json")).expect("Config fixture"),
+            manifest.0[0]
+                .layers()
+                .iter()
+                .map(|layer| docker::tests::layer_content(layer).len() as u64)
+                .collect(),
+        );
+
+        (manifest, blobs)
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn manifest_to_index() {
+        let (manifest, blobs) = fixtures();
+        let index = super::manifest_to_index(&manifest, std::slice::from_ref(&blobs))
+            .expect("Could not convert manifest");
+
+        assert_eq!(index.manifests().len(), 1, "Unexpected number of manifests");
+
+        let descriptor = &index.manifests()[0];
+        let image_manifest = super::manifest_item_to_image_manifest(&manifest.0[0], &blobs)
+            .expect("Could not convert manifest item");
+        let image_manifest_content =
+            serde_json::to_vec(&image_manifest).expect("Could not serialize manifest");
+
+        assert_eq!(
+            descriptor.digest(),
+            &Digest::sha256(&image_manifest_content).to_string()
+        );
+        assert_eq!(descriptor.size(), image_manifest_content.len() as i64);
+        assert_eq!(descriptor.media_type(), &MediaType::DockerManifest.into());
+        assert_eq!(
+            descriptor
+                .annotations()
+                .as_ref()
+                .and_then(|annotations| annotations.get(REF_NAME_ANNOTATION)),
+            Some(&"15.4".to_owned())
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn manifest_item_to_image_manifest() {
+        let (manifest, blobs) = fixtures();
+        let config = ImageConfiguration::from_slice(blobs.config()).expect("Config fixture");
+        let image_manifest = super::manifest_item_to_image_manifest(&manifest.0[0], &blobs)
+            .expect("Could not convert manifest item");
+
+        assert_eq!(
+            image_manifest.config().digest(),
+            &Digest::sha256(blobs.config()).to_string(),
+            "Configuration descriptor does not reference the stored blob"
+        );
+        assert_eq!(image_manifest.config().size(), blobs.config().len() as i64);
+        assert_eq!(
+            image_manifest.config().media_type(),
+            &MediaType::DockerConfig.into()
+        );
+        assert_eq!(
+            image_manifest
+                .layers()
+                .iter()
+                .map(|layer| (layer.digest().clone(), layer.size() as u64))
+                .collect::<Vec<_>>(),
+            config
+                .oci_spec()
+                .rootfs()
+                .diff_ids()
+                .iter()
+                .cloned()
+                .zip(blobs.layer_sizes().iter().copied())
+                .collect::<Vec<_>>(),
+            "Layer descriptors do not reference the stored layers"
+        );
+        assert!(image_manifest
+            .layers()
+            .iter()
+            .all(|layer| *layer.media_type() == MediaType::DockerLayer.into()));
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn foreign_layer() {
+        let (manifest, blobs) = fixtures();
+        let config = ImageConfiguration::from_slice(blobs.config()).expect("Config fixture");
+        let foreign_diff_id = config.oci_spec().rootfs().diff_ids()[1].clone();
+        let foreign_manifest =
+            ImageManifest::from_file(docker::tests::test_data_path("manifest_layer_sources.json"))
+                .expect("Manifest fixture");
+        let (_, source) = foreign_manifest.0[0]
+            .layer_sources_iter()
+            .next()
+            .expect("Foreign layer fixture");
+        let item = ManifestItemBuilder::default()
+            .config(manifest.0[0].config())
+            .layers(manifest.0[0].layers().clone())
+            .layer_sources(BTreeMap::from([(foreign_diff_id, source.clone())]))
+            .build()
+            .expect("Build manifest item");
+
+        let image_manifest = super::manifest_item_to_image_manifest(&item, &blobs)
+            .expect("Could not convert manifest item");
+
+        assert_eq!(
+            &image_manifest.layers()[1],
+            source,
+            "Foreign layer is not described by its source"
+        );
+        assert_eq!(
+            image_manifest.layers()[0].size() as u64,
+            blobs.layer_sizes()[0]
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn layer_size_count_mismatch() {
+        let (manifest, blobs) = fixtures();
+        let blobs = ItemBlobs::new(blobs.config().clone(), vec![1024]);
+        let result = super::manifest_item_to_image_manifest(&manifest.0[0], &blobs);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::LayerCountMismatch {
+                        expected: 3,
+                        actual: 1
+                    }
+                )))
+            ),
+            "Layer size count mismatch was not reported: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn item_blobs_from_dir() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let (manifest, blobs) = fixtures();
+
+        docker::tests::test_image_dir(dir.path());
+
+        assert_eq!(
+            ItemBlobs::from_dir(&manifest.0[0], dir.path()).expect("Could not read blobs"),
+            blobs
+        );
+
+        let missing_layer = &manifest.0[0].layers()[2];
+
+        fs::remove_file(dir.path().join(missing_layer)).expect("Could not remove layer");
+
+        let result = ItemBlobs::from_dir(&manifest.0[0], dir.path());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(Error::MissingLayer(ref layer))))
+                    if layer == missing_layer
+            ),
+            "Missing layer was not reported: {result:?}"
+        );
+    }
+
+    #[cfg(all(feature = "fs", feature = "oci-distribution"))]
+    #[test]
+    fn manifest_item_to_oci_distribution_manifest() {
+        let (manifest, blobs) = fixtures();
+        let config = ImageConfiguration::from_slice(blobs.config()).expect("Config fixture");
+        let oci_manifest =
+            super::manifest_item_to_oci_distribution_manifest(&manifest.0[0], &blobs)
+                .expect("Could not convert manifest item");
+
+        assert_eq!(oci_manifest.schema_version, 2);
+        assert_eq!(
+            oci_manifest.media_type,
+            Some(MediaType::DockerManifest.to_string())
+        );
+        assert_eq!(
+            oci_manifest.config.digest,
+            Digest::sha256(blobs.config()).to_string(),
+            "Configuration descriptor does not reference the stored blob"
+        );
+        assert_eq!(oci_manifest.config.size, blobs.config().len() as i64);
+        assert_eq!(
+            oci_manifest.config.media_type,
+            MediaType::DockerConfig.to_string()
+        );
+        assert_eq!(
+            oci_manifest
+                .layers
+                .iter()
+                .map(|layer| (layer.digest.clone(), layer.size as u64))
+                .collect::<Vec<_>>(),
+            config
+                .oci_spec()
+                .rootfs()
+                .diff_ids()
+                .iter()
+                .cloned()
+                .zip(blobs.layer_sizes().iter().copied())
+                .collect::<Vec<_>>(),
+            "Layer descriptors do not reference the stored layers"
+        );
+        assert_eq!(
+            oci_manifest.to_string(),
+            manifest.0[0]
+                .to_oci_distribution_manifest(&blobs)
+                .expect("Could not convert manifest item")
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn configuration_count_mismatch() {
+        let item = ManifestItemBuilder::default()
+            .config("config.json")
+            .layers(vec!["3b05311756d9/layer.tar".to_owned()])
+            .build()
+            .expect("Build manifest item");
+        let manifest = ImageManifest::from_iter([item]);
+        let result = super::manifest_to_index(&manifest, &[]);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::ConfigurationCountMismatch {
+                        expected: 1,
+                        actual: 0
+                    }
+                )))
+            ),
+            "Configuration count mismatch was not reported: {result:?}"
+        );
+    }
+}
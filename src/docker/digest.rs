@@ -0,0 +1,275 @@
+//! Content addressable digests, as used by layer hashes, configuration references and `diff_ids`.
+
+use crate::docker;
+use crate::error::{ParsleyError, ParsleyResult};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+use std::str::FromStr;
+
+/// Hashing algorithms supported by digests.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Algorithm {
+    /// SHA-256, the algorithm used by Docker and OCI by default.
+    Sha256,
+    /// SHA-384.
+    Sha384,
+    /// SHA-512.
+    Sha512,
+}
+
+impl Algorithm {
+    /// Name of the algorithm, as it appears in the `algorithm:hex` form.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// Length of the hex encoded hash produced by the algorithm.
+    pub fn hex_len(&self) -> usize {
+        match self {
+            Self::Sha256 => 64,
+            Self::Sha384 => 96,
+            Self::Sha512 => 128,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ParsleyError;
+
+    /// Attempts to parse an algorithm from its name.
+    ///
+    /// # Errors
+    ///
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the algorithm is not supported.
+    fn from_str(s: &str) -> ParsleyResult<Self> {
+        match s {
+            "sha256" => Ok(Self::Sha256),
+            "sha384" => Ok(Self::Sha384),
+            "sha512" => Ok(Self::Sha512),
+            _ => Err(invalid(s, "unsupported algorithm")),
+        }
+    }
+}
+
+/// A digest in the `algorithm:hex` form (e.g. `sha256:1c3daa06...`).
+///
+/// The hex encoded part is validated against the expected length of the algorithm and must be
+/// lowercase, as required by the OCI specification.
+///
+/// # Example
+/// ```
+/// use std::str::FromStr;
+/// use parsley::docker::digest::{Algorithm, Digest};
+///
+/// let digest = Digest::from_str(
+///     "sha256:1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(*digest.algorithm(), Algorithm::Sha256);
+/// ```
+#[derive(Getters, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Digest {
+    /// Algorithm used to compute the hash.
+    #[getset(get = "pub")]
+    algorithm: Algorithm,
+    /// Hex encoded hash.
+    #[getset(get = "pub")]
+    hex: String,
+}
+
+impl Digest {
+    /// Attempts to create a digest from an algorithm and a hex encoded hash.
+    ///
+    /// # Errors
+    ///
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the hash is not a valid lowercase hex
+    /// string of the length expected by the algorithm.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::digest::{Algorithm, Digest};
+    ///
+    /// let digest = Digest::new(
+    ///     Algorithm::Sha256,
+    ///     "44c358f2ad25734bc7de467b050a1a0f343602ce0322b73a225c0cb59b2c1297",
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn new<S: Into<String>>(algorithm: Algorithm, hex: S) -> ParsleyResult<Self> {
+        let hex = hex.into();
+
+        if hex.len() != algorithm.hex_len() {
+            return Err(invalid(
+                &format!("{algorithm}:{hex}"),
+                &format!(
+                    "expected {} hex characters for {algorithm}, got {}",
+                    algorithm.hex_len(),
+                    hex.len()
+                ),
+            ));
+        }
+
+        if !hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+            return Err(invalid(
+                &format!("{algorithm}:{hex}"),
+                "hash is not a lowercase hex string",
+            ));
+        }
+
+        Ok(Self { algorithm, hex })
+    }
+
+    /// Computes the SHA-256 digest of a content.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::digest::Digest;
+    ///
+    /// assert_eq!(
+    ///     Digest::sha256(b"").to_string(),
+    ///     "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    /// );
+    /// ```
+    pub fn sha256(content: &[u8]) -> Self {
+        Self {
+            algorithm: Algorithm::Sha256,
+            hex: format!("{:x}", <Sha256 as sha2::Digest>::digest(content)),
+        }
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.hex)
+    }
+}
+
+impl FromStr for Digest {
+    type Err = ParsleyError;
+
+    /// Attempts to parse a digest from the `algorithm:hex` form.
+    ///
+    /// # Errors
+    ///
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the digest is malformed.
+    ///
+    /// # Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use parsley::docker::digest::Digest;
+    ///
+    /// assert!(Digest::from_str("sha256:abc").is_err());
+    /// ```
+    fn from_str(s: &str) -> ParsleyResult<Self> {
+        let (algorithm, hex) = s
+            .split_once(':')
+            .ok_or_else(|| invalid(s, "expected the algorithm:hex form"))?;
+        let algorithm = Algorithm::from_str(algorithm)
+            .map_err(|_| invalid(s, &format!("unsupported algorithm {algorithm}")))?;
+
+        Self::new(algorithm, hex)
+    }
+}
+
+impl Serialize for Digest {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Digest {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let digest: String = Deserialize::deserialize(deserializer)?;
+
+        Self::from_str(&digest).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Creates the error reported for a malformed digest.
+fn invalid(digest: &str, reason: &str) -> ParsleyError {
+    docker::Error::InvalidDigest {
+        digest: digest.to_owned(),
+        reason: reason.to_owned(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(
+        "sha256:1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1",
+        Algorithm::Sha256;
+        "SHA-256"
+    )]
+    #[test_case(
+        "sha384:38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b",
+        Algorithm::Sha384;
+        "SHA-384"
+    )]
+    #[test_case(
+        "sha512:cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        Algorithm::Sha512;
+        "SHA-512"
+    )]
+    fn valid(s: &str, algorithm: Algorithm) {
+        let digest = Digest::from_str(s).expect("Valid digest rejected");
+
+        assert_eq!(*digest.algorithm(), algorithm);
+        assert_eq!(digest.to_string(), s, "Digest does not round trip");
+    }
+
+    #[test_case("1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1"; "Missing algorithm")]
+    #[test_case("md5:d41d8cd98f00b204e9800998ecf8427e"; "Unsupported algorithm")]
+    #[test_case("sha256:1c3daa"; "Short hash")]
+    #[test_case("sha256:1C3DAA06574284614DB07A23682AB6D1C344F09F8093EE10E5DE4152A51677A1"; "Uppercase hash")]
+    #[test_case("sha256:zc3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1"; "Non hex hash")]
+    fn malformed(s: &str) {
+        let result = Digest::from_str(s);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::InvalidDigest { ref digest, .. })) if digest == s
+            ),
+            "Invalid digest was not rejected: {result:?}"
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let s = "\"sha256:1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1\"";
+        let digest: Digest = serde_json::from_str(s).expect("Could not deserialize digest");
+
+        assert_eq!(
+            serde_json::to_string(&digest).expect("Failed to serialize"),
+            s,
+            "Digest is not serialized transparently"
+        );
+        assert!(
+            serde_json::from_str::<Digest>("\"sha256:1c3daa\"").is_err(),
+            "Malformed digest was deserialized"
+        );
+    }
+}
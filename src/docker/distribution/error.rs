@@ -8,6 +8,40 @@ pub enum Error {
     MissingRepositories,
 
     /// Error caused by invalid content of repositories file
-    #[error("repositories file is missing")]
+    #[error("invalid content in repositories file")]
     InvalidRepositories,
+
+    /// Error caused by content that is not a registry manifest
+    #[error("invalid registry manifest: {0}")]
+    InvalidRegistryManifest(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_registry_manifest_display() {
+        assert_eq!(
+            Error::InvalidRegistryManifest("missing schemaVersion".to_owned()).to_string(),
+            "invalid registry manifest: missing schemaVersion"
+        );
+    }
+
+    #[test]
+    fn distinct_display() {
+        assert_eq!(
+            Error::MissingRepositories.to_string(),
+            "repositories file is missing"
+        );
+        assert_eq!(
+            Error::InvalidRepositories.to_string(),
+            "invalid content in repositories file"
+        );
+        assert_ne!(
+            Error::MissingRepositories.to_string(),
+            Error::InvalidRepositories.to_string(),
+            "Repositories errors are indistinguishable"
+        );
+    }
 }
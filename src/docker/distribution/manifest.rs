@@ -0,0 +1,252 @@
+use crate::docker;
+use crate::docker::distribution::error::Error;
+use crate::error::{ParsleyError, ParsleyResult};
+use crate::util;
+use oci_spec::image::{ImageIndex, ImageManifest, MediaType};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::str::FromStr;
+
+/// A `manifest.json` as served by registries or copied by `skopeo`, in contrast to the flat array
+/// produced by `docker save` and modeled by [ImageManifest](crate::docker::image::ImageManifest).
+///
+/// The document is either a schema 2 image manifest describing a single image or an image index
+/// (manifest list) pointing to an image manifest per platform.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::distribution::RegistryManifest;
+///
+/// match RegistryManifest::from_file("manifest.json").unwrap() {
+///     RegistryManifest::Image(manifest) => println!("{} layers", manifest.layers().len()),
+///     RegistryManifest::Index(index) => println!("{} platforms", index.manifests().len()),
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RegistryManifest {
+    /// A schema 2 image manifest, describing the configuration and layers of a single image.
+    Image(Box<ImageManifest>),
+    /// An image index (manifest list), describing the image manifest of every platform.
+    Index(ImageIndex),
+}
+
+impl RegistryManifest {
+    /// Attempts to load a registry manifest from a file.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file does not exist
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the file is not a registry manifest (e.g.
+    /// a `docker save` manifest)
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let manifest = distribution::RegistryManifest::from_file("manifest.json").unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
+        Self::from_value(util::json::from_file(path)?)
+    }
+
+    /// Attempts to load a registry manifest from bytes of JSON text.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the content is not a registry manifest
+    /// (e.g. a `docker save` manifest)
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let bytes = vec![];
+    /// let manifest = distribution::RegistryManifest::from_slice(&bytes).unwrap();
+    /// ```
+    pub fn from_slice(v: &[u8]) -> ParsleyResult<Self> {
+        Self::from_value(util::json::from_slice(v)?)
+    }
+
+    /// Picks the dialect of the manifest out of its top-level JSON shape.
+    fn from_value(json: serde_json::Value) -> ParsleyResult<Self> {
+        let invalid = |reason: &str| -> ParsleyError {
+            docker::Error::from(Error::InvalidRegistryManifest(reason.to_owned())).into()
+        };
+        let object = match json {
+            serde_json::Value::Object(ref object) => object,
+            serde_json::Value::Array(_) => {
+                return Err(invalid(
+                    "found an array, which is the shape of a docker save manifest",
+                ))
+            }
+            _ => return Err(invalid("expected a JSON object")),
+        };
+
+        if !object.contains_key("schemaVersion") {
+            return Err(invalid("missing schemaVersion"));
+        }
+
+        if is_index(object) {
+            Ok(Self::Index(serde_json::from_value(json)?))
+        } else {
+            Ok(Self::Image(serde_json::from_value(json)?))
+        }
+    }
+
+    /// Media type declared by the manifest, if any.
+    pub fn media_type(&self) -> Option<&MediaType> {
+        match self {
+            Self::Image(manifest) => manifest.media_type().as_ref(),
+            Self::Index(index) => index.media_type().as_ref(),
+        }
+    }
+}
+
+/// Whether a registry manifest object is an image index, as told by its `manifests` or, lacking
+/// those, by its media type.
+pub(crate) fn is_index(object: &serde_json::Map<String, serde_json::Value>) -> bool {
+    object.contains_key("manifests")
+        || object
+            .get("mediaType")
+            .and_then(|media_type| media_type.as_str())
+            .map_or(false, |media_type| {
+                docker::media_type::MediaType::from(media_type).is_index()
+            })
+}
+
+impl FromStr for RegistryManifest {
+    type Err = ParsleyError;
+
+    /// Attempts to load a registry manifest from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the content is not a registry manifest
+    /// (e.g. a `docker save` manifest)
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use std::str::FromStr;
+    /// use parsley::docker::distribution;
+    ///
+    /// let s = "";
+    /// let manifest = distribution::RegistryManifest::from_str(&s).unwrap();
+    /// ```
+    fn from_str(s: &str) -> ParsleyResult<Self> {
+        Self::from_value(util::json::from_str(s)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const IMAGE_MANIFEST: &str = r#"{
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.manifest.v1+json",
+        "config": {
+            "mediaType": "application/vnd.oci.image.config.v1+json",
+            "digest": "sha256:ee56d70bcdf1aeca472a9899de653eb4d72f4a3ac31d9b0b95e677488ce766f3",
+            "size": 7023
+        },
+        "layers": [
+            {
+                "mediaType": "application/vnd.oci.image.layer.v1.tar+gzip",
+                "digest": "sha256:1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1",
+                "size": 32654
+            }
+        ]
+    }"#;
+
+    const IMAGE_INDEX: &str = r#"{
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.docker.distribution.manifest.list.v2+json",
+        "manifests": [
+            {
+                "mediaType": "application/vnd.docker.distribution.manifest.v2+json",
+                "digest": "sha256:e692418e4cbaf90ca69d05a66403747baa33ee08806650b51fab815ad7fc331f",
+                "size": 7143,
+                "platform": {
+                    "architecture": "arm64",
+                    "os": "linux",
+                    "variant": "v8"
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn image_manifest() {
+        let manifest =
+            RegistryManifest::from_str(IMAGE_MANIFEST).expect("Could not deserialize manifest");
+
+        assert!(
+            matches!(manifest, RegistryManifest::Image(ref manifest) if manifest.layers().len() == 1),
+            "Image manifest was not recognized: {manifest:?}"
+        );
+        assert_eq!(manifest.media_type(), Some(&MediaType::ImageManifest));
+    }
+
+    #[test]
+    fn image_index() {
+        let manifest = RegistryManifest::from_slice(IMAGE_INDEX.as_bytes())
+            .expect("Could not deserialize index");
+
+        assert!(
+            matches!(manifest, RegistryManifest::Index(ref index) if index.manifests().len() == 1),
+            "Image index was not recognized: {manifest:?}"
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let manifest =
+            RegistryManifest::from_str(IMAGE_INDEX).expect("Could not deserialize index");
+        let serialized_manifest = serde_json::to_string(&manifest).expect("Failed to serialize");
+
+        assert_eq!(
+            RegistryManifest::from_str(&serialized_manifest)
+                .expect("Could not deserialize from serialization"),
+            manifest,
+            "Deserialized manifest from serialized manifest is different"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn docker_save_manifest() {
+        let path = docker::tests::test_data_path("manifest.json");
+        let result = RegistryManifest::from_file(path);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::DistributionError(
+                    Error::InvalidRegistryManifest(ref reason)
+                ))) if reason.contains("docker save")
+            ),
+            "Docker save manifest was not rejected: {result:?}"
+        );
+    }
+
+    #[test_case("\"manifest\""; "Scalar")]
+    #[test_case("{\"layers\": []}"; "Missing schema version")]
+    fn wrong_shape(s: &str) {
+        let result = RegistryManifest::from_str(s);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::DistributionError(
+                    Error::InvalidRegistryManifest(_)
+                )))
+            ),
+            "Wrong shape was not rejected: {result:?}"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 //! Docker Distribution Specification types and definitions.
 
-pub(crate) mod error;
+pub mod error;
+mod manifest;
 mod repository;
 
+pub use manifest::*;
 pub use repository::*;
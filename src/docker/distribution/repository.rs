@@ -1,7 +1,15 @@
+use crate::docker;
+use crate::docker::distribution::error::Error;
+use crate::docker::image::ImageManifest;
 use crate::error::{ParsleyError, ParsleyResult};
 use crate::util;
+use getset::Getters;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{btree_map, BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{Read, Write};
+use std::ops::{Deref, DerefMut};
+#[cfg(feature = "fs")]
 use std::path::Path;
 use std::str::FromStr;
 
@@ -9,16 +17,475 @@ use std::str::FromStr;
 ///
 /// Implemented as a tuple struct in order to implement foreign traits on the type, impossible if
 /// defined as a type alias.
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+///
+/// Besides the `"tag": "hash"` form, the `"tag": {"Layer": "hash"}` form of some legacy files is
+/// accepted when deserializing, while serialization always produces the former.
+#[derive(Debug, Default, Eq, PartialEq, Clone, Serialize)]
 pub struct Repository(BTreeMap<String, String>);
 
+/// Layer hash of a tag, in any of the forms found in `repositories` files.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TagEntry {
+    Hash(String),
+    Layer {
+        #[serde(rename = "Layer")]
+        layer: String,
+    },
+}
+
+impl<'de> Deserialize<'de> for Repository {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let tags: BTreeMap<String, TagEntry> = Deserialize::deserialize(deserializer)?;
+
+        Ok(Self(
+            tags.into_iter()
+                .map(|(tag, entry)| match entry {
+                    TagEntry::Hash(hash) | TagEntry::Layer { layer: hash } => (tag, hash),
+                })
+                .collect(),
+        ))
+    }
+}
+
 /// Map from image name to different tags.
 ///
 /// Implemented as a tuple struct in order to implement foreign traits on the type, impossible if
 /// defined as a type alias.
-#[derive(Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Repositories(BTreeMap<String, Repository>);
 
+/// Tags on which a `repositories` file and an image manifest disagree, as reported by
+/// [Repositories::compare_repo_tags](Repositories::compare_repo_tags).
+///
+/// Tags are written as `image:tag`, the form of [ManifestItem::repo_tags](
+/// crate::docker::image::ManifestItem::repo_tags).
+#[derive(Getters, Clone, Debug, Default, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct RepoTagsMismatch {
+    /// Tags of the `repositories` file that no manifest item lists, ordered.
+    missing_in_manifest: Vec<String>,
+    /// Tags listed by manifest items that the `repositories` file lacks, ordered.
+    missing_in_repositories: Vec<String>,
+}
+
+impl Repository {
+    /// Sets the layer hash of a tag, returning the previous hash of the tag, if any.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::distribution::Repository;
+    ///
+    /// let mut repository = Repository::default();
+    /// repository.insert("15.4", "44c358f2ad25734bc7de467b050a1a0f343602ce0322b73a225c0cb59b2c1297");
+    /// ```
+    pub fn insert<T, H>(&mut self, tag: T, hash: H) -> Option<String>
+    where
+        T: Into<String>,
+        H: Into<String>,
+    {
+        self.0.insert(tag.into(), hash.into())
+    }
+
+    /// Retrieves the layer hash of a tag.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let repositories = distribution::Repositories::from_file("repositories").unwrap();
+    /// let hash = repositories.get("postgres").and_then(|repository| repository.get("15.4"));
+    /// ```
+    pub fn get(&self, tag: &str) -> Option<&str> {
+        self.0.get(tag).map(String::as_str)
+    }
+
+    /// Iterates over the tags of the repository, in order.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+}
+
+impl Repositories {
+    /// Sets the layer hash of an image tag, creating the image repository if needed. Returns the
+    /// previous hash of the tag, if any.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::distribution::Repositories;
+    ///
+    /// let mut repositories = Repositories::default();
+    /// repositories.insert(
+    ///     "postgres",
+    ///     "15.4",
+    ///     "44c358f2ad25734bc7de467b050a1a0f343602ce0322b73a225c0cb59b2c1297",
+    /// );
+    /// ```
+    pub fn insert<I, T, H>(&mut self, image: I, tag: T, hash: H) -> Option<String>
+    where
+        I: Into<String>,
+        T: Into<String>,
+        H: Into<String>,
+    {
+        self.0.entry(image.into()).or_default().insert(tag, hash)
+    }
+
+    /// Retrieves the repository of an image.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let repositories = distribution::Repositories::from_file("repositories").unwrap();
+    /// let repository = repositories.get("postgres");
+    /// ```
+    pub fn get(&self, image: &str) -> Option<&Repository> {
+        self.0.get(image)
+    }
+
+    /// Iterates over the images and their repositories, ordered by image name.
+    pub fn iter(&self) -> btree_map::Iter<'_, String, Repository> {
+        self.0.iter()
+    }
+
+    /// Splits the image names into their registry and repository path, in order, applying the
+    /// defaults of [Reference](crate::docker::reference::Reference) (e.g. `postgres` becomes
+    /// `("docker.io", "library/postgres")`). The names themselves are kept verbatim.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if an image name is malformed.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let repositories = distribution::Repositories::from_file("repositories").unwrap();
+    ///
+    /// for (registry, repository) in repositories.normalized_names().unwrap() {
+    ///     println!("{repository} from {registry}");
+    /// }
+    /// ```
+    pub fn normalized_names(&self) -> ParsleyResult<Vec<(String, String)>> {
+        self.0
+            .keys()
+            .map(|name| docker::reference::normalize_name(name))
+            .collect()
+    }
+
+    /// Adds the images and tags of `other`, the hash of `other` winning when a tag is present in
+    /// both.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::distribution::Repositories;
+    ///
+    /// let mut repositories = Repositories::default();
+    /// let mut other = Repositories::default();
+    ///
+    /// repositories.insert("postgres", "15.4", "old");
+    /// other.insert("postgres", "15.4", "new");
+    /// other.insert("ubuntu", "22.04", "hash");
+    /// repositories.merge(other);
+    ///
+    /// assert_eq!(repositories.get("postgres").and_then(|r| r.get("15.4")), Some("new"));
+    /// assert_eq!(repositories.len(), 2);
+    /// ```
+    pub fn merge(&mut self, other: Repositories) {
+        self.merge_with(other, |_, _, _, new| new.to_owned())
+    }
+
+    /// Adds the images and tags of `other`, calling `resolve` with the image, the tag, the
+    /// existing hash and the hash of `other` to pick the hash of a tag present in both.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::distribution::Repositories;
+    ///
+    /// let mut repositories = Repositories::default();
+    /// let mut other = Repositories::default();
+    ///
+    /// repositories.insert("postgres", "15.4", "old");
+    /// other.insert("postgres", "15.4", "new");
+    /// repositories.merge_with(other, |_, _, existing, _| existing.to_owned());
+    ///
+    /// assert_eq!(repositories.get("postgres").and_then(|r| r.get("15.4")), Some("old"));
+    /// ```
+    pub fn merge_with<F>(&mut self, other: Repositories, mut resolve: F)
+    where
+        F: FnMut(&str, &str, &str, &str) -> String,
+    {
+        for (image, repository) in other {
+            let existing = self.0.entry(image.clone()).or_default();
+
+            for (tag, hash) in repository.0 {
+                match existing.0.entry(tag) {
+                    btree_map::Entry::Vacant(entry) => {
+                        entry.insert(hash);
+                    }
+                    btree_map::Entry::Occupied(mut entry) => {
+                        let resolved = resolve(&image, entry.key(), entry.get(), &hash);
+
+                        entry.insert(resolved);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves the tags of image `old` under image `new`, e.g. when retagging
+    /// `postgres` as `registry.example.com/postgres`. Returns whether a rename occurred, which
+    /// is not the case when `old` does not exist or `new` already does, the latter requiring
+    /// [rename_image_force](Repositories::rename_image_force).
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::distribution::Repositories;
+    ///
+    /// let mut repositories = Repositories::default();
+    /// repositories.insert("postgres", "15.4", "hash");
+    ///
+    /// assert!(repositories.rename_image("postgres", "registry.example.com/postgres"));
+    /// assert!(repositories.get("postgres").is_none());
+    /// ```
+    pub fn rename_image(&mut self, old: &str, new: &str) -> bool {
+        if self.0.contains_key(new) {
+            return false;
+        }
+
+        self.rename_image_force(old, new)
+    }
+
+    /// Moves the tags of image `old` under image `new`, like
+    /// [rename_image](Repositories::rename_image), but replacing the tags of `new` if it already
+    /// exists. Returns whether a rename occurred, which is not the case when `old` does not
+    /// exist.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::distribution::Repositories;
+    ///
+    /// let mut repositories = Repositories::default();
+    /// repositories.insert("postgres", "15.4", "new");
+    /// repositories.insert("db", "15.3", "old");
+    ///
+    /// assert!(repositories.rename_image_force("postgres", "db"));
+    /// assert_eq!(repositories.get("db").and_then(|r| r.get("15.4")), Some("new"));
+    /// assert_eq!(repositories.len(), 1);
+    /// ```
+    pub fn rename_image_force(&mut self, old: &str, new: &str) -> bool {
+        if old == new {
+            return self.0.contains_key(old);
+        }
+
+        match self.0.remove(old) {
+            Some(repository) => {
+                self.0.insert(new.to_owned(), repository);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cross-checks the tags with the `RepoTags` of the items of `manifest`, which must agree
+    /// within a consistent image store.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::{distribution, image};
+    ///
+    /// let repositories = distribution::Repositories::from_file("repositories").unwrap();
+    /// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+    /// let mismatch = repositories.compare_repo_tags(&image_manifest);
+    ///
+    /// for tag in mismatch.missing_in_manifest() {
+    ///     println!("{tag} is not part of the manifest");
+    /// }
+    /// ```
+    pub fn compare_repo_tags(&self, manifest: &ImageManifest) -> RepoTagsMismatch {
+        let repositories_tags = self
+            .0
+            .iter()
+            .flat_map(|(image, repository)| {
+                repository.tags().map(move |tag| format!("{image}:{tag}"))
+            })
+            .collect::<BTreeSet<_>>();
+        let manifest_tags = manifest
+            .iter()
+            .flat_map(|item| item.repo_tags().iter().cloned())
+            .collect::<BTreeSet<_>>();
+
+        RepoTagsMismatch {
+            missing_in_manifest: repositories_tags
+                .difference(&manifest_tags)
+                .cloned()
+                .collect(),
+            missing_in_repositories: manifest_tags
+                .difference(&repositories_tags)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Number of images.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no images.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Gives access to the whole map API, e.g. `range` or `retain`, on top of the methods of
+/// [Repository](Repository).
+///
+/// # Example
+/// ```
+/// use parsley::docker::distribution::Repository;
+///
+/// let mut repository = Repository::default();
+/// repository.insert("15.4", "44c358f2ad25");
+/// repository.insert("latest", "44c358f2ad25");
+///
+/// assert_eq!(repository.iter().count(), 2);
+/// assert!(repository.contains_key("latest"));
+/// ```
+impl Deref for Repository {
+    type Target = BTreeMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// # Example
+/// ```
+/// use parsley::docker::distribution::Repository;
+///
+/// let mut repository = Repository::default();
+/// repository.insert("15.4", "44c358f2ad25");
+/// repository.retain(|tag, _| tag != "15.4");
+///
+/// assert!(repository.is_empty());
+/// ```
+impl DerefMut for Repository {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Gives access to the whole map API, e.g. `keys` or `retain`, on top of the methods of
+/// [Repositories](Repositories).
+///
+/// # Example
+/// ```
+/// use parsley::docker::distribution::Repositories;
+///
+/// let mut repositories = Repositories::default();
+/// repositories.insert("postgres", "15.4", "44c358f2ad25");
+///
+/// assert_eq!(repositories.keys().collect::<Vec<_>>(), ["postgres"]);
+/// ```
+impl Deref for Repositories {
+    type Target = BTreeMap<String, Repository>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// # Example
+/// ```
+/// use parsley::docker::distribution::Repositories;
+///
+/// let mut repositories = Repositories::default();
+/// repositories.insert("postgres", "15.4", "44c358f2ad25");
+/// repositories.retain(|image, _| image != "postgres");
+///
+/// assert!(repositories.is_empty());
+/// ```
+impl DerefMut for Repositories {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use parsley::docker::distribution;
+///
+/// let repositories = distribution::Repositories::from_file("repositories").unwrap();
+///
+/// for (image, repository) in repositories {
+///     println!("{image}: {:?}", repository.tags().collect::<Vec<_>>());
+/// }
+/// ```
+impl IntoIterator for Repositories {
+    type Item = (String, Repository);
+    type IntoIter = btree_map::IntoIter<String, Repository>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use parsley::docker::distribution;
+///
+/// let repositories = distribution::Repositories::from_file("repositories").unwrap();
+///
+/// for (image, repository) in &repositories {
+///     println!("{image}: {:?}", repository.tags().collect::<Vec<_>>());
+/// }
+/// ```
+impl<'a> IntoIterator for &'a Repositories {
+    type Item = (&'a String, &'a Repository);
+    type IntoIter = btree_map::Iter<'a, String, Repository>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use parsley::docker::distribution;
+///
+/// let repositories = distribution::Repositories::from_file("repositories").unwrap();
+/// let postgres: distribution::Repositories = repositories
+///     .into_iter()
+///     .filter(|(image, _)| image == "postgres")
+///     .collect();
+/// ```
+impl FromIterator<(String, Repository)> for Repositories {
+    fn from_iter<I: IntoIterator<Item = (String, Repository)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// Formats repositories data as written by the Docker daemon: compact JSON with images and tags in
+/// order and no trailing newline.
+///
+/// # Example
+/// ```
+/// use parsley::docker::distribution::Repositories;
+///
+/// let mut repositories = Repositories::default();
+/// repositories.insert("postgres", "15.4", "44c358f2ad25");
+///
+/// assert_eq!(repositories.to_string(), "{\"postgres\":{\"15.4\":\"44c358f2ad25\"}}");
+/// ```
+impl fmt::Display for Repositories {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&serde_json::to_string(self).map_err(|_| fmt::Error)?)
+    }
+}
+
 impl FromStr for Repositories {
     type Err = ParsleyError;
 
@@ -26,7 +493,9 @@ impl FromStr for Repositories {
     ///
     /// # Errors
     ///
-    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the content is not shaped as repositories
+    /// data
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the content is not valid JSON.
     ///
     /// # Example
     /// ``` no_run
@@ -37,7 +506,53 @@ impl FromStr for Repositories {
     /// let repositories = distribution::Repositories::from_str(&s).unwrap();
     /// ```
     fn from_str(s: &str) -> ParsleyResult<Self> {
-        util::json::from_str(s)
+        Self::from_value(util::json::from_str(s)?)
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use parsley::docker::distribution;
+///
+/// let bytes = std::fs::read("repositories").unwrap();
+/// let repositories = distribution::Repositories::try_from(bytes.as_slice()).unwrap();
+/// ```
+impl TryFrom<&[u8]> for Repositories {
+    type Error = ParsleyError;
+
+    fn try_from(v: &[u8]) -> ParsleyResult<Self> {
+        Self::from_slice(v)
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use parsley::docker::distribution;
+///
+/// let s = std::fs::read_to_string("repositories").unwrap();
+/// let repositories = distribution::Repositories::try_from(s.as_str()).unwrap();
+/// ```
+impl TryFrom<&str> for Repositories {
+    type Error = ParsleyError;
+
+    fn try_from(s: &str) -> ParsleyResult<Self> {
+        Self::from_str(s)
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use std::path::Path;
+/// use parsley::docker::distribution;
+///
+/// let repositories = distribution::Repositories::try_from(Path::new("repositories")).unwrap();
+/// ```
+#[cfg(feature = "fs")]
+impl TryFrom<&Path> for Repositories {
+    type Error = ParsleyError;
+
+    fn try_from(path: &Path) -> ParsleyResult<Self> {
+        Self::from_file(path)
     }
 }
 
@@ -46,7 +561,9 @@ impl Repositories {
     ///
     /// # Errors
     /// [ParsleyError::Io](ParsleyError::Io) if the file does not exist
-    /// [ParsleyError::Io](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the content is not shaped as repositories
+    /// data
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the content is not valid JSON.
     ///
     /// # Example
     /// ``` no_run
@@ -54,14 +571,40 @@ impl Repositories {
     ///
     /// let repositories = distribution::Repositories::from_file("repositories").unwrap();
     /// ```
+    #[cfg(feature = "fs")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
-        util::json::from_file(path)
+        Self::from_value(util::json::from_file(path)?)
+    }
+
+    /// Attempts to asynchronously load repositories data from a file.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file does not exist
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the content is not shaped as repositories
+    /// data
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the content is not valid JSON.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// # async fn load() {
+    /// let repositories = distribution::Repositories::from_file_async("repositories")
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
+        Self::from_value(util::json::from_file_async(path).await?)
     }
 
     /// Attempts to load repositories data from bytes of JSON text.
     ///
     /// # Errors
-    /// [ParsleyError::Io](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the content is not shaped as repositories
+    /// data
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the content is not valid JSON.
     ///
     /// # Example
     /// ``` no_run
@@ -71,21 +614,454 @@ impl Repositories {
     /// let repositories = distribution::Repositories::from_slice(&bytes).unwrap();
     /// ```
     pub fn from_slice(v: &[u8]) -> ParsleyResult<Self> {
-        util::json::from_slice(v)
+        Self::from_value(util::json::from_slice(v)?)
+    }
+
+    /// Attempts to load repositories data from a stream of JSON text, such as a tar entry or an
+    /// HTTP body.
+    ///
+    /// The stream is read as is, thus wrapping an unbuffered one in a
+    /// [BufReader](std::io::BufReader) is recommended.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be read
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the content is not shaped as repositories
+    /// data
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the content is not valid JSON.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let file = std::fs::File::open("repositories").unwrap();
+    /// let repositories = distribution::Repositories::from_reader(std::io::BufReader::new(file))
+    ///     .unwrap();
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> ParsleyResult<Self> {
+        Self::from_value(util::json::from_reader(reader)?)
+    }
+
+    /// Attempts to load repositories data from a stream of JSON text, reading at most
+    /// `max_bytes`, such that untrusted inputs cannot exhaust the memory.
+    ///
+    /// # Errors
+    /// [ParsleyError::SizeLimitExceeded](ParsleyError::SizeLimitExceeded) if the stream holds more
+    /// than `max_bytes`
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be read
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the content is not shaped as repositories
+    /// data
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the content cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let file = std::fs::File::open("repositories").unwrap();
+    /// let repositories = distribution::Repositories::from_reader_limited(file, 1 << 20).unwrap();
+    /// ```
+    pub fn from_reader_limited<R: Read>(reader: R, max_bytes: u64) -> ParsleyResult<Self> {
+        Self::from_value(util::json::from_reader_limited(reader, max_bytes)?)
+    }
+
+    /// Attempts to write repositories data to a file, as written by the Docker daemon: compact JSON
+    /// with images and tags in order and no trailing newline. If the file already exists, it will
+    /// be overwritten.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file cannot be created or written
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the repositories data cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let mut repositories = distribution::Repositories::from_file("repositories").unwrap();
+    /// repositories.insert(
+    ///     "postgres",
+    ///     "latest",
+    ///     "44c358f2ad25734bc7de467b050a1a0f343602ce0322b73a225c0cb59b2c1297",
+    /// );
+    /// repositories.to_file("repositories").unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> ParsleyResult<()> {
+        util::json::to_file(self, path)
+    }
+
+    /// Attempts to write repositories data to a stream, as written by the Docker daemon.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be written
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the repositories data cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::distribution;
+    ///
+    /// let repositories = distribution::Repositories::from_file("repositories").unwrap();
+    /// let mut writer = Vec::new();
+    /// repositories.to_writer(&mut writer).unwrap();
+    /// ```
+    pub fn to_writer<W: Write>(&self, writer: W) -> ParsleyResult<()> {
+        util::json::to_writer(self, writer)
+    }
+
+    /// Normalizes the repositories data out of any JSON value, reporting unknown shapes as
+    /// invalid content.
+    fn from_value(json: serde_json::Value) -> ParsleyResult<Self> {
+        serde_json::from_value(json)
+            .map_err(|_| docker::Error::from(Error::InvalidRepositories).into())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::docker;
+    use test_case::test_case;
 
+    #[cfg(feature = "fs")]
     #[test]
     fn deserialize() {
         let path = docker::tests::test_data_path("repositories.json");
         Repositories::from_file(path).expect("Could not deserialize from file {path}");
     }
 
+    #[cfg(feature = "fs")]
+    #[test]
+    fn from_reader() {
+        let path = docker::tests::test_data_path("repositories.json");
+        let content = std::fs::read(&path).expect("Could not read repositories fixture");
+        let repositories = Repositories::from_reader(std::io::Cursor::new(content))
+            .expect("Could not deserialize from reader");
+
+        assert_eq!(
+            repositories,
+            Repositories::from_file(path).expect("Could not deserialize from file")
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn layer_form() {
+        let repositories =
+            Repositories::from_file(docker::tests::test_data_path("repositories_layer.json"))
+                .expect("Could not deserialize layer form");
+
+        assert_eq!(
+            repositories,
+            Repositories::from_file(docker::tests::test_data_path("repositories.json"))
+                .expect("Could not deserialize hash form"),
+            "Layer form does not normalize to the hash form"
+        );
+    }
+
+    #[test_case("{\"postgres\": {\"15.4\": 1}}"; "Number hash")]
+    #[test_case("{\"postgres\": {\"15.4\": {\"Digest\": \"abc\"}}}"; "Unknown object")]
+    #[test_case("{\"postgres\": \"15.4\"}"; "Flat repository")]
+    #[test_case("[]"; "Array")]
+    fn invalid_shape(s: &str) {
+        let result = Repositories::from_str(s);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::DistributionError(
+                    Error::InvalidRepositories
+                )))
+            ),
+            "Invalid repositories were not reported: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn to_file() {
+        let path = docker::tests::test_data_path("repositories.json");
+        let repositories = Repositories::from_file(&path).expect("Could not deserialize from file");
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let output_path = dir.path().join("repositories");
+        let expected = serde_json::to_string(
+            &serde_json::from_str::<serde_json::Value>(
+                &std::fs::read_to_string(path).expect("Could not read fixture"),
+            )
+            .expect("Invalid fixture"),
+        )
+        .expect("Could not compact fixture");
+
+        repositories
+            .to_file(&output_path)
+            .expect("Could not write to file");
+
+        assert_eq!(
+            std::fs::read_to_string(output_path).expect("Could not read written file"),
+            expected,
+            "Written repositories do not match the daemon format"
+        );
+        assert_eq!(repositories.to_string(), expected);
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn registry_prefixed_names() {
+        let path = docker::tests::test_data_path("repositories_registry.json");
+        let content = std::fs::read_to_string(&path).expect("Could not read fixture");
+        let repositories = Repositories::from_str(&content).expect("Could not deserialize");
+
+        assert_eq!(
+            repositories.to_string(),
+            serde_json::to_string(
+                &serde_json::from_str::<serde_json::Value>(&content).expect("Invalid fixture")
+            )
+            .expect("Could not compact fixture"),
+            "Registry prefixed names do not round trip"
+        );
+        assert!(
+            repositories.get("localhost:5000/postgres").is_some(),
+            "Registry prefixed name was altered"
+        );
+        assert_eq!(
+            repositories
+                .normalized_names()
+                .expect("Could not normalize names"),
+            [
+                ("localhost:5000".to_owned(), "postgres".to_owned()),
+                ("docker.io".to_owned(), "library/postgres".to_owned()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn lookup() {
+        let path = docker::tests::test_data_path("repositories.json");
+        let repositories = Repositories::from_file(path).expect("Could not deserialize from file");
+        let repository = repositories
+            .get("postgres")
+            .expect("Missing postgres repository");
+
+        assert_eq!(repositories.len(), 1);
+        assert!(!repositories.is_empty());
+        assert_eq!(
+            repositories
+                .iter()
+                .map(|(image, _)| image.as_str())
+                .collect::<Vec<_>>(),
+            vec!["postgres"]
+        );
+        assert!(repositories.get("ubuntu").is_none());
+        assert_eq!(repository.tags().collect::<Vec<_>>(), vec!["15.4"]);
+        assert_eq!(
+            repository.get("15.4"),
+            Some("44c358f2ad25734bc7de467b050a1a0f343602ce0322b73a225c0cb59b2c1297")
+        );
+        assert!(repository.get("latest").is_none());
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn iterate() {
+        let path = docker::tests::test_data_path("repositories.json");
+        let repositories = Repositories::from_file(path).expect("Could not deserialize from file");
+        let images = (&repositories)
+            .into_iter()
+            .map(|(image, _)| image.clone())
+            .collect::<Vec<_>>();
+        let collected = repositories.clone().into_iter().collect::<Repositories>();
+
+        assert_eq!(images, vec!["postgres".to_owned()]);
+        assert_eq!(collected, repositories);
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn insert() {
+        let path = docker::tests::test_data_path("repositories.json");
+        let expected = Repositories::from_file(path).expect("Could not deserialize from file");
+        let mut repositories = Repositories::default();
+
+        assert!(repositories
+            .insert("postgres", "15.4", "previous")
+            .is_none());
+        assert_eq!(
+            repositories.insert(
+                "postgres",
+                "15.4",
+                "44c358f2ad25734bc7de467b050a1a0f343602ce0322b73a225c0cb59b2c1297"
+            ),
+            Some("previous".to_owned())
+        );
+        assert_eq!(repositories, expected);
+        assert_eq!(
+            serde_json::to_value(&repositories).expect("Failed to serialize"),
+            serde_json::to_value(&expected).expect("Failed to serialize"),
+            "Built repositories do not serialize to the fixture shape"
+        );
+    }
+
+    fn repositories(entries: &[(&str, &str, &str)]) -> Repositories {
+        let mut repositories = Repositories::default();
+
+        for (image, tag, hash) in entries {
+            repositories.insert(*image, *tag, *hash);
+        }
+
+        repositories
+    }
+
+    #[test_case(
+        &[("postgres", "15.4", "a")],
+        &[("ubuntu", "22.04", "b")],
+        &[("postgres", "15.4", "a"), ("ubuntu", "22.04", "b")];
+        "Disjoint images"
+    )]
+    #[test_case(
+        &[("postgres", "15.4", "a")],
+        &[("postgres", "latest", "b")],
+        &[("postgres", "15.4", "a"), ("postgres", "latest", "b")];
+        "Overlapping images"
+    )]
+    #[test_case(
+        &[("postgres", "15.4", "a"), ("postgres", "latest", "a")],
+        &[("postgres", "latest", "b")],
+        &[("postgres", "15.4", "a"), ("postgres", "latest", "b")];
+        "Conflicting tags"
+    )]
+    fn merge(
+        entries: &[(&str, &str, &str)],
+        other: &[(&str, &str, &str)],
+        expected: &[(&str, &str, &str)],
+    ) {
+        let mut merged = repositories(entries);
+
+        merged.merge(repositories(other));
+
+        assert_eq!(merged, repositories(expected));
+    }
+
+    #[test]
+    fn merge_with() {
+        let mut merged = repositories(&[("postgres", "15.4", "a"), ("postgres", "latest", "a")]);
+        let mut conflicts = Vec::new();
+
+        merged.merge_with(
+            repositories(&[("postgres", "latest", "b"), ("ubuntu", "22.04", "c")]),
+            |image, tag, existing, new| {
+                conflicts.push(format!("{image}:{tag} {existing} {new}"));
+                existing.to_owned()
+            },
+        );
+
+        assert_eq!(conflicts, vec!["postgres:latest a b"]);
+        assert_eq!(
+            merged,
+            repositories(&[
+                ("postgres", "15.4", "a"),
+                ("postgres", "latest", "a"),
+                ("ubuntu", "22.04", "c")
+            ])
+        );
+    }
+
+    #[test_case("postgres", "db" => true; "Renamed")]
+    #[test_case("mysql", "db" => false; "Missing source")]
+    #[test_case("postgres", "ubuntu" => false; "Existing target")]
+    fn rename_image(old: &str, new: &str) -> bool {
+        let original = repositories(&[("postgres", "15.4", "a"), ("ubuntu", "22.04", "b")]);
+        let mut renamed = original.clone();
+        let result = renamed.rename_image(old, new);
+
+        if result {
+            assert_eq!(
+                renamed,
+                repositories(&[("db", "15.4", "a"), ("ubuntu", "22.04", "b")])
+            );
+        } else {
+            assert_eq!(renamed, original, "Repositories were altered");
+        }
+
+        result
+    }
+
+    #[test]
+    fn rename_image_force() {
+        let mut renamed = repositories(&[("postgres", "15.4", "a"), ("ubuntu", "22.04", "b")]);
+
+        assert!(renamed.rename_image_force("postgres", "ubuntu"));
+        assert_eq!(renamed, repositories(&[("ubuntu", "15.4", "a")]));
+        assert!(!renamed.rename_image_force("postgres", "ubuntu"));
+        assert!(renamed.rename_image_force("ubuntu", "ubuntu"));
+        assert_eq!(renamed, repositories(&[("ubuntu", "15.4", "a")]));
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn compare_repo_tags() {
+        let repositories =
+            Repositories::from_file(docker::tests::test_data_path("repositories_registry.json"))
+                .expect("Could not deserialize from file");
+        let manifest =
+            ImageManifest::from_file(docker::tests::test_data_path("manifest_parent.json"))
+                .expect("Could not deserialize manifest");
+        let mismatch = repositories.compare_repo_tags(&manifest);
+
+        assert_eq!(
+            mismatch.missing_in_manifest(),
+            &["localhost:5000/postgres:15.4"]
+        );
+        assert_eq!(
+            mismatch.missing_in_repositories(),
+            &["postgres:15.4-custom"]
+        );
+
+        let repositories =
+            Repositories::from_file(docker::tests::test_data_path("repositories.json"))
+                .expect("Could not deserialize from file");
+        let manifest = ImageManifest::from_file(docker::tests::test_data_path("manifest.json"))
+            .expect("Could not deserialize manifest");
+
+        assert_eq!(
+            repositories.compare_repo_tags(&manifest),
+            RepoTagsMismatch::default(),
+            "Consistent tags were reported"
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn from_file_async() {
+        let path = docker::tests::test_data_path("repositories.json");
+        let repositories = Repositories::from_file_async(&path)
+            .await
+            .expect("Could not deserialize from file");
+
+        assert_eq!(
+            repositories,
+            Repositories::from_file(path).expect("Could not deserialize from file")
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn try_from() {
+        let path = docker::tests::test_data_path("repositories.json");
+        let content = std::fs::read_to_string(&path).expect("Could not read fixture");
+        let expected = Repositories::from_file(&path).expect("Could not deserialize from file");
+
+        assert_eq!(
+            Repositories::try_from(content.as_bytes()).expect("Could not convert from bytes"),
+            expected
+        );
+        assert_eq!(
+            Repositories::try_from(content.as_str()).expect("Could not convert from string"),
+            expected
+        );
+        assert_eq!(
+            Repositories::try_from(path.as_path()).expect("Could not convert from path"),
+            expected
+        );
+    }
+
+    #[cfg(feature = "fs")]
     #[test]
     fn serde() {
         let path = docker::tests::test_data_path("repositories.json");
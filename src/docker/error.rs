@@ -1,13 +1,96 @@
+use crate::docker::archive;
 use crate::docker::distribution;
 use crate::docker::image;
+use crate::docker::oci_layout;
 use thiserror::Error;
 
 /// Error type for handling Docker related failures
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("docker image error: {0}")]
-    ImageError(image::error::Error),
+    ImageError(#[from] image::error::Error),
 
     #[error("docker distribution error: {0}")]
-    DistributionError(distribution::error::Error),
+    DistributionError(#[from] distribution::error::Error),
+
+    #[error("docker archive error: {0}")]
+    ArchiveError(#[from] archive::error::Error),
+
+    #[error("oci image layout error: {0}")]
+    OciLayoutError(#[from] oci_layout::error::Error),
+
+    #[error("invalid digest {digest}: {reason}")]
+    InvalidDigest { digest: String, reason: String },
+
+    #[error("invalid reference {reference}: {reason}")]
+    InvalidReference { reference: String, reason: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ParsleyError, ParsleyResult};
+
+    fn docker_result<E>(error: E) -> Result<(), Error>
+    where
+        Error: From<E>,
+    {
+        Err(error)?
+    }
+
+    fn parsley_result<E>(error: E) -> ParsleyResult<()>
+    where
+        Error: From<E>,
+    {
+        docker_result(error)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn image_error_conversion() {
+        let result = parsley_result(image::error::Error::MissingImageManifest);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(Error::ImageError(
+                    image::error::Error::MissingImageManifest
+                )))
+            ),
+            "Image error did not convert into ParsleyError::Docker: {result:?}"
+        );
+    }
+
+    #[test]
+    fn distribution_error_conversion() {
+        let result = parsley_result(distribution::error::Error::MissingRepositories);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(Error::DistributionError(
+                    distribution::error::Error::MissingRepositories
+                )))
+            ),
+            "Distribution error did not convert into ParsleyError::Docker: {result:?}"
+        );
+    }
+
+    #[test]
+    fn archive_error_conversion() {
+        let result = parsley_result(archive::error::Error::InvalidArchive(
+            std::io::ErrorKind::UnexpectedEof.into(),
+        ));
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(Error::ArchiveError(
+                    archive::error::Error::InvalidArchive(_)
+                )))
+            ),
+            "Archive error did not convert into ParsleyError::Docker: {result:?}"
+        );
+    }
 }
@@ -1,13 +1,23 @@
+use crate::docker;
+use crate::docker::image::error::Error;
 use crate::error::{ParsleyError, ParsleyResult};
 
 use crate::util;
 use derive_builder::Builder;
-use getset::Getters;
+use getset::{Getters, MutGetters, Setters};
 use oci_spec;
+use serde::de::DeserializeSeed;
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{Read, Write};
+#[cfg(feature = "fs")]
 use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 /// Docker OCI image configuration according.
 /// The image is composed by a base set of specifications that comply with the OCI specifications
@@ -26,14 +36,21 @@ use std::time::Duration;
 ///     .build()
 ///     .unwrap();
 /// ```
-#[derive(Builder, Getters, Clone, Debug, Default, Eq, PartialEq)]
+///
+/// Building a configuration without `oci_spec` fails with
+/// [Error::MissingRequiredField](Error::MissingRequiredField).
+///
+/// Keys known to the OCI specification always belong to `oci_spec`: when deserializing they are
+/// never captured by the extension and when serializing the OCI values take precedence over any
+/// extension value set under the same key, such that round trips are deterministic.
+#[derive(Builder, Getters, MutGetters, Setters, Clone, Debug, Default, Eq, PartialEq)]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "ParsleyError")
+    build_fn(validate = "Self::validate", error = "ParsleyError")
 )]
-#[getset(get = "pub")]
+#[getset(get = "pub", get_mut = "pub", set = "pub")]
 pub struct ImageConfiguration {
     /// Standard OCI specifications.
     oci_spec: oci_spec::image::ImageConfiguration,
@@ -52,10 +69,16 @@ impl Serialize for ImageConfiguration {
     {
         let mut merged_config = serde_json::to_value(&self.oci_spec)
             .map_err(|err| <S::Error as serde::ser::Error>::custom(err.to_string()))?;
-        let docker_extension = serde_json::to_value(&self.docker_oci_extension)
-            .map_err(|err| <S::Error as serde::ser::Error>::custom(err.to_string()))?;
 
-        util::json::merge(&mut merged_config, docker_extension);
+        // Without extension, the OCI specification is serialized as is
+        if let Some(docker_oci_extension) = &self.docker_oci_extension {
+            let mut docker_extension = serde_json::to_value(docker_oci_extension)
+                .map_err(|err| <S::Error as serde::ser::Error>::custom(err.to_string()))?;
+
+            // Mirror the deserialization: the OCI specification wins over conflicting keys
+            strip_oci_fields(&mut docker_extension, &merged_config);
+            util::json::merge(&mut merged_config, docker_extension);
+        }
 
         merged_config.serialize(serializer)
     }
@@ -64,24 +87,15 @@ impl Serialize for ImageConfiguration {
 /// Custom deserialization implementation since, both OCI specification and Docker extension
 /// fields are required to be extracted from the same field (e.g. `config` field of the image
 /// specification).
+///
+/// The fields are deserialized straight from the deserializer, thus its errors are passed through
+/// as is, along with the position of the invalid value within the JSON text.
 impl<'de> Deserialize<'de> for ImageConfiguration {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        // Retrieve the JSON, as the 'config' field is used by both OCI spec and Docker extensions
-        let full_json: serde_json::Value = Deserialize::deserialize(deserializer)?;
-
-        // Deserialize the JSON twice: once for OCI spec and once for Docker extensions
-        let oci_spec = Deserialize::deserialize(full_json.clone())
-            .map_err(|json_err| serde::de::Error::custom(json_err.to_string()))?;
-        let docker_oci_extension = Deserialize::deserialize(full_json)
-            .map_err(|json_err| serde::de::Error::custom(json_err.to_string()))?;
-
-        Ok(Self {
-            docker_oci_extension,
-            oci_spec,
-        })
+        ImageConfigurationSeed(&Cell::new(None)).deserialize(deserializer)
     }
 }
 
@@ -113,6 +127,51 @@ pub struct ImageConfigurationExtension {
     /// Extra fields in the `config` field.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     config: Option<ConfigExtension>,
+    /// Top level fields known neither to the OCI specification nor to this extension, preserved
+    /// such that they survive a round trip.
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_json::Value>,
+}
+
+impl ImageConfigurationExtension {
+    /// Converts the extension into the JSON fields it adds to a configuration, e.g. for splicing
+    /// them into another JSON document. Unset fields are skipped, as when serializing.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the extension cannot be serialized.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let extension = image::ImageConfigurationExtension::default();
+    ///
+    /// assert_eq!(extension.to_value().unwrap(), serde_json::json!({}));
+    /// ```
+    pub fn to_value(&self) -> ParsleyResult<serde_json::Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    /// Attempts to load an extension from the JSON fields of a configuration. Fields unknown to
+    /// the extension are kept in [extra](ImageConfigurationExtension::extra).
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the extension cannot be deserialized.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let extension = image::ImageConfigurationExtension::from_value(
+    ///     serde_json::json!({"docker_version": "20.10.23"}),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(extension.config().is_none());
+    /// ```
+    pub fn from_value(value: serde_json::Value) -> ParsleyResult<Self> {
+        Ok(serde_json::from_value(value)?)
+    }
 }
 
 /// Covers all extra fields that Docker adds in `config` field of the OCI image specifications.
@@ -145,10 +204,10 @@ pub struct ImageConfigurationExtension {
 pub struct ConfigExtension {
     /// Memory limit (in bytes).
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    memory: Option<u64>,
+    memory: Option<MemoryLimit>,
     /// Total memory usage (memory + swap).
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    memory_swap: Option<u64>,
+    memory_swap: Option<MemoryLimit>,
     /// CPU shares (relative weight vs. other containers).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     cpu_shares: Option<u16>,
@@ -163,8 +222,9 @@ pub struct ConfigExtension {
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
-        // Healthcheck does not respect the naming pattern, thus the alias
-        alias = "Healthcheck"
+        // Docker writes `Healthcheck` rather than the PascalCase name, which is still accepted
+        rename = "Healthcheck",
+        alias = "HealthCheck"
     )]
     health_check: Option<HealthcheckConfig>,
     /// Defines "trigger" instructions to be executed at a later time, when the image is used as the
@@ -181,6 +241,127 @@ pub struct ConfigExtension {
     /// This field is set by the SHELL instruction in a Dockerfile, and *must* be written in JSON form.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     shell: Option<Vec<String>>,
+    /// Fields of `config` known neither to the OCI specification nor to this extension, preserved
+    /// such that they survive a round trip.
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Shell used by Docker for the *shell* form of commands on Linux and other non Windows systems.
+const DEFAULT_SHELL: &[&str] = &["/bin/sh", "-c"];
+
+/// Shell used by Docker for the *shell* form of commands on Windows.
+const WINDOWS_DEFAULT_SHELL: &[&str] = &["cmd", "/S", "/C"];
+
+impl ConfigExtension {
+    /// Checks that the resource limits are consistent with what Docker accepts: the total memory
+    /// limit (memory + swap) cannot be lower than the memory limit and CPU shares cannot be 0.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the limits are inconsistent.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let config_extension = image::ConfigExtensionBuilder::default()
+    ///     .memory(2048_u64)
+    ///     .memory_swap(1024_u64)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(config_extension.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> ParsleyResult<()> {
+        if let (Some(MemoryLimit::Bytes(memory)), Some(MemoryLimit::Bytes(memory_swap))) =
+            (self.memory, self.memory_swap)
+        {
+            if memory_swap < memory {
+                return Err(docker::Error::from(Error::InvalidMemoryConfiguration {
+                    memory,
+                    memory_swap,
+                })
+                .into());
+            }
+        }
+
+        if self.cpu_shares == Some(0) {
+            return Err(docker::Error::from(Error::InvalidCpuConfiguration).into());
+        }
+
+        Ok(())
+    }
+
+    /// Shell used for the *shell* form of commands: the configured one or, if none is set, the
+    /// default shell of `os`, i.e. `["cmd", "/S", "/C"]` on Windows and `["/bin/sh", "-c"]`
+    /// otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use oci_spec::image::Os;
+    /// use parsley::docker::image;
+    ///
+    /// let config_extension = image::ConfigExtension::default();
+    ///
+    /// assert_eq!(config_extension.shell_or_default(&Os::Windows), ["cmd", "/S", "/C"]);
+    /// ```
+    pub fn shell_or_default(&self, os: &oci_spec::image::Os) -> Vec<String> {
+        if let Some(shell) = &self.shell {
+            return shell.clone();
+        }
+
+        let shell = match os {
+            oci_spec::image::Os::Windows => WINDOWS_DEFAULT_SHELL,
+            _ => DEFAULT_SHELL,
+        };
+
+        shell.iter().map(ToString::to_string).collect()
+    }
+
+    /// Attempts to load the Docker fields of a `config` fragment from bytes of JSON text.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the fragment cannot be deserialized.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let config_extension =
+    ///     image::ConfigExtension::from_slice(br#"{"Memory": 2048, "Hostname": ""}"#).unwrap();
+    ///
+    /// assert_eq!(config_extension.memory(), &Some(image::MemoryLimit::Bytes(2048)));
+    /// assert!(config_extension.extra().contains_key("Hostname"));
+    /// ```
+    pub fn from_slice(v: &[u8]) -> ParsleyResult<Self> {
+        util::json::from_slice(v)
+    }
+}
+
+impl FromStr for ConfigExtension {
+    type Err = ParsleyError;
+
+    /// Attempts to load the Docker fields of a `config` fragment from a JSON string.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the fragment cannot be deserialized.
+    ///
+    /// # Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use parsley::docker::image;
+    ///
+    /// let config_extension =
+    ///     image::ConfigExtension::from_str(r#"{"Shell": ["/bin/bash", "-c"]}"#).unwrap();
+    ///
+    /// assert_eq!(
+    ///     config_extension.shell(),
+    ///     &Some(vec!["/bin/bash".to_owned(), "-c".to_owned()])
+    /// );
+    /// ```
+    fn from_str(s: &str) -> ParsleyResult<Self> {
+        util::json::from_str(s)
+    }
 }
 
 impl FromStr for ImageConfiguration {
@@ -190,22 +371,158 @@ impl FromStr for ImageConfiguration {
     ///
     /// # Errors
     ///
-    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized,
+    /// including when `s` is empty.
     ///
     /// # Example
-    /// ``` no_run
+    /// ```
     /// use std::str::FromStr;
     /// use parsley::docker::image;
     ///
-    /// let s = "";
-    /// let image_config = image::ImageConfiguration::from_str(&s).unwrap();
+    /// let s = r#"{"architecture":"amd64","os":"linux","history":[],"rootfs":{"type":"layers","diff_ids":[]}}"#;
+    /// let image_config = image::ImageConfiguration::from_str(s).unwrap();
+    ///
+    /// assert_eq!(image_config, image::ImageConfiguration::default());
+    /// assert!(image::ImageConfiguration::from_str("").is_err());
     /// ```
     fn from_str(s: &str) -> ParsleyResult<Self> {
-        util::json::from_str(s)
+        Self::from_read(serde_json::de::StrRead::new(s))
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use parsley::docker::image;
+///
+/// let bytes = std::fs::read("config.json").unwrap();
+/// let image_config = image::ImageConfiguration::try_from(bytes.as_slice()).unwrap();
+/// ```
+impl TryFrom<&[u8]> for ImageConfiguration {
+    type Error = ParsleyError;
+
+    fn try_from(v: &[u8]) -> ParsleyResult<Self> {
+        Self::from_slice(v)
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use parsley::docker::image;
+///
+/// let s = std::fs::read_to_string("config.json").unwrap();
+/// let image_config = image::ImageConfiguration::try_from(s.as_str()).unwrap();
+/// ```
+impl TryFrom<&str> for ImageConfiguration {
+    type Error = ParsleyError;
+
+    fn try_from(s: &str) -> ParsleyResult<Self> {
+        Self::from_str(s)
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use std::path::Path;
+/// use parsley::docker::image;
+///
+/// let image_config = image::ImageConfiguration::try_from(Path::new("config.json")).unwrap();
+/// ```
+#[cfg(feature = "fs")]
+impl TryFrom<&Path> for ImageConfiguration {
+    type Error = ParsleyError;
+
+    fn try_from(path: &Path) -> ParsleyResult<Self> {
+        Self::from_file(path)
+    }
+}
+
+/// Formats the image configuration as pretty printed JSON, merging the OCI specification and the
+/// Docker extension as when serializing.
+///
+/// # Example
+/// ```
+/// use parsley::docker::image;
+///
+/// let image_config = image::ImageConfiguration::default();
+///
+/// println!("{image_config}");
+/// ```
+impl fmt::Display for ImageConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&util::json::to_string_pretty(self).map_err(|_| fmt::Error)?)
     }
 }
 
 impl ImageConfiguration {
+    /// Creates a configuration out of the OCI specification only, without Docker extension, as
+    /// for images built by OCI tooling.
+    ///
+    /// # Example
+    /// ```
+    /// use oci_spec::image as oci_image;
+    /// use parsley::docker::image;
+    ///
+    /// let image_config =
+    ///     image::ImageConfiguration::from_oci(oci_image::ImageConfiguration::default());
+    ///
+    /// assert!(image_config.docker_oci_extension().is_none());
+    /// ```
+    pub fn from_oci(spec: oci_spec::image::ImageConfiguration) -> Self {
+        Self {
+            oci_spec: spec,
+            docker_oci_extension: None,
+        }
+    }
+
+    /// Overlays a Docker extension onto the configuration, leaving the OCI specification intact.
+    ///
+    /// The fields set by `extension` override the ones of the current extension, if any, while
+    /// the others are kept, as done when merging both under the same `config` field on
+    /// serialization.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if an extension cannot be serialized or the
+    /// overlay cannot be deserialized.
+    ///
+    /// # Example
+    /// ```
+    /// use oci_spec::image as oci_image;
+    /// use parsley::docker::image;
+    ///
+    /// let mut image_config =
+    ///     image::ImageConfiguration::from_oci(oci_image::ImageConfiguration::default());
+    /// let extension = image::ImageConfigurationExtensionBuilder::default()
+    ///     .config(image::ConfigExtensionBuilder::default().memory(2048_u64).build().unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// image_config.merge_extension(extension).unwrap();
+    ///
+    /// assert_eq!(
+    ///     image_config
+    ///         .docker_oci_extension()
+    ///         .as_ref()
+    ///         .and_then(|extension| extension.config().as_ref())
+    ///         .and_then(|config| *config.memory()),
+    ///     Some(image::MemoryLimit::Bytes(2048))
+    /// );
+    /// ```
+    pub fn merge_extension(&mut self, extension: ImageConfigurationExtension) -> ParsleyResult<()> {
+        let merged = match self.docker_oci_extension.take() {
+            Some(existing) => {
+                let mut merged = existing.to_value()?;
+
+                util::json::merge(&mut merged, extension.to_value()?);
+                ImageConfigurationExtension::from_value(merged)?
+            }
+            None => extension,
+        };
+
+        self.docker_oci_extension = Some(merged);
+
+        Ok(())
+    }
+
     /// Attempts to load an image configuration from a file.
     ///
     /// # Errors
@@ -218,8 +535,34 @@ impl ImageConfiguration {
     ///
     /// let image_config = image::ImageConfiguration::from_file("1bc9978a2dd04fb656d9055670b5beee1c948ca3b65cade7783c2d3bab306141.json").unwrap();
     /// ```
+    #[cfg(feature = "fs")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
-        util::json::from_file(path)
+        Self::from_reader(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+
+    /// Attempts to asynchronously load an image configuration from a file.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file does not exist
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// # async fn load() {
+    /// let image_config = image::ImageConfiguration::from_file_async("config.json")
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
+        let content = tokio::fs::read(path).await?;
+
+        tokio::task::spawn_blocking(move || Self::from_slice(&content))
+            .await
+            .map_err(std::io::Error::from)?
     }
 
     /// Attempts to load an image configuration from bytes of JSON text.
@@ -235,84 +578,1780 @@ impl ImageConfiguration {
     /// let image_config = image::ImageConfiguration::from_slice(&bytes).unwrap();
     /// ```
     pub fn from_slice(v: &[u8]) -> ParsleyResult<Self> {
-        util::json::from_slice(v)
+        Self::from_read(serde_json::de::SliceRead::new(v))
     }
-}
 
-/// HealthcheckConfig holds configuration settings for the HEALTHCHECK feature.
-///
-/// For more details refer to the [Go Spec](https://github.com/moby/moby/blob/f6fa56194f1c9cfd2e4ae41a17f75ab6b04f82df/image/spec/specs-go/v1/image.go#L35).
-///
-/// # Example
-/// ```
-/// use std::time::Duration;
-/// use parsley::docker::image;
-///
-/// let check = image::HealthcheckConfigBuilder::default()
-///     .test(Vec::default())
-///     .interval(Duration::default())
-///     .timeout(Duration::default())
-///     .start_interval(Duration::default())
-///     .retries(u32::default())
-///     .build()
-///     .unwrap();
-/// ```
-#[derive(Builder, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(rename_all = "PascalCase")]
-#[builder(
-    default,
-    pattern = "owned",
-    setter(into, strip_option),
-    build_fn(error = "ParsleyError")
-)]
-pub struct HealthcheckConfig {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    test: Option<Vec<String>>,
-    #[serde(
-        skip_serializing_if = "Option::is_none",
-        serialize_with = "util::json::serialize_duration",
-        deserialize_with = "util::json::deserialize_duration"
-    )]
-    interval: Option<Duration>,
-    #[serde(
-        skip_serializing_if = "Option::is_none",
-        serialize_with = "util::json::serialize_duration",
-        deserialize_with = "util::json::deserialize_duration"
-    )]
-    timeout: Option<Duration>,
-    #[serde(
-        skip_serializing_if = "Option::is_none",
-        serialize_with = "util::json::serialize_duration",
-        deserialize_with = "util::json::deserialize_duration"
-    )]
-    start_interval: Option<Duration>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    retries: Option<u32>,
-}
+    /// Attempts to load an image configuration from a stream of JSON text, such as a tar entry or an HTTP body.
+    ///
+    /// The stream is read as is, thus wrapping an unbuffered one in a
+    /// [BufReader](std::io::BufReader) is recommended.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let file = std::fs::File::open("config.json").unwrap();
+    /// let image_config = image::ImageConfiguration::from_reader(std::io::BufReader::new(file))
+    ///     .unwrap();
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> ParsleyResult<Self> {
+        Self::from_read(serde_json::de::IoRead::new(reader))
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::docker;
-    use oci_spec::image;
-    use std::collections::HashMap;
+    /// Attempts to load an image configuration from a stream of JSON text, reading at most
+    /// `max_bytes`, such that untrusted inputs cannot exhaust the memory.
+    ///
+    /// # Errors
+    /// [ParsleyError::SizeLimitExceeded](ParsleyError::SizeLimitExceeded) if the stream holds more
+    /// than `max_bytes`
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let file = std::fs::File::open("config.json").unwrap();
+    /// let image_config = image::ImageConfiguration::from_reader_limited(file, 1 << 20).unwrap();
+    /// ```
+    pub fn from_reader_limited<R: Read>(reader: R, max_bytes: u64) -> ParsleyResult<Self> {
+        Self::from_slice(&util::json::read_limited(reader, max_bytes)?)
+    }
 
-    fn config() -> ImageConfiguration {
-        let docker_oci_extension = ImageConfigurationExtensionBuilder::default()
-            .config(
-                ConfigExtensionBuilder::default()
-                    .memory(2048_u64)
-                    .memory_swap(4096_u64)
-                    .cpu_shares(8_u16)
-                    .args_escaped(false)
-                    .shell(vec![
-                        "/bin/bash".to_owned(),
-                        "-o".to_owned(),
-                        "pipefail".to_owned(),
-                        "-c".to_owned(),
-                    ])
-                    .on_build(vec!["a".to_owned(), "b".to_owned()])
-                    .health_check(
+    /// Attempts to load an image configuration from a gzip compressed file (e.g. `config.json.gz`).
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file does not exist or cannot be decompressed
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the file is not gzip compressed
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_gzip_file("config.json.gz").unwrap();
+    /// ```
+    #[cfg(all(feature = "fs", feature = "gzip"))]
+    pub fn from_gzip_file<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
+        Self::from_gzip_reader(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+
+    /// Attempts to load an image configuration from gzip compressed bytes of JSON text.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the bytes cannot be decompressed
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the bytes are not gzip compressed
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let bytes = vec![];
+    /// let image_config = image::ImageConfiguration::from_gzip_slice(&bytes).unwrap();
+    /// ```
+    #[cfg(feature = "gzip")]
+    pub fn from_gzip_slice(v: &[u8]) -> ParsleyResult<Self> {
+        Self::from_gzip_reader(v)
+    }
+
+    /// Attempts to load an image configuration from a gzip compressed stream of JSON text.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be read or decompressed
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the stream is not gzip compressed
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let file = std::fs::File::open("config.json.gz").unwrap();
+    /// let image_config = image::ImageConfiguration::from_gzip_reader(file).unwrap();
+    /// ```
+    #[cfg(feature = "gzip")]
+    pub fn from_gzip_reader<R: Read>(mut reader: R) -> ParsleyResult<Self> {
+        let mut magic = Vec::new();
+
+        reader.by_ref().take(2).read_to_end(&mut magic)?;
+
+        if docker::layer::Compression::detect(&magic) != docker::layer::Compression::Gzip {
+            return Err(docker::Error::from(Error::NotGzipCompressed).into());
+        }
+
+        Self::from_reader(flate2::read::MultiGzDecoder::new(
+            std::io::Cursor::new(magic).chain(reader),
+        ))
+    }
+
+    /// Attempts to load an image configuration from a JSON string whose healthcheck durations are
+    /// encoded in the given format.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if a healthcheck duration overflows a count of
+    /// nanoseconds
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let s = r#"{"architecture":"amd64","os":"linux","history":[],"rootfs":{"type":"layers","diff_ids":[]}}"#;
+    /// let image_config =
+    ///     image::ImageConfiguration::from_str_with_duration_format(s, image::DurationFormat::Seconds)
+    ///         .unwrap();
+    /// ```
+    pub fn from_str_with_duration_format(s: &str, format: DurationFormat) -> ParsleyResult<Self> {
+        let mut json: serde_json::Value = util::json::from_str(s)?;
+
+        convert_healthcheck_durations(&mut json, |value| format.to_nanos(value))?;
+
+        Self::from_value(json)
+    }
+
+    /// Attempts to load an image configuration from a JSON string, rejecting the fields unknown to
+    /// the Docker image format, such as typos in hand-written configurations.
+    ///
+    /// Fields of the Docker image format that this crate does not model (e.g. `docker_version` or
+    /// `config.Hostname`) are accepted, such that configurations written by Docker always parse.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the configuration holds an unknown field
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let s = r#"{"architecture": "amd64", "os": "linux", "config": {"Memmory": 2048},
+    ///     "rootfs": {"type": "layers", "diff_ids": []}}"#;
+    ///
+    /// assert!(image::ImageConfiguration::from_str_strict(s).is_err());
+    /// ```
+    pub fn from_str_strict(s: &str) -> ParsleyResult<Self> {
+        let image_config = Self::from_str(s)?;
+
+        match image_config.unknown_field() {
+            Some(field) => Err(docker::Error::from(Error::UnknownField(field)).into()),
+            None => Ok(image_config),
+        }
+    }
+
+    /// Path of the first captured field that is unknown to the Docker image format, if any.
+    fn unknown_field(&self) -> Option<String> {
+        let extension = self.docker_oci_extension.as_ref()?;
+        let top_level = extension
+            .extra
+            .keys()
+            .find(|key| !DOCKER_FIELDS.contains(&key.as_str()))
+            .cloned();
+        let config = || {
+            extension
+                .config
+                .as_ref()?
+                .extra
+                .keys()
+                .find(|key| !DOCKER_CONFIG_FIELDS.contains(&key.as_str()))
+                .map(|key| format!("config.{key}"))
+        };
+
+        top_level.or_else(config)
+    }
+
+    /// Deserializes a configuration out of its JSON text, rejecting trailing characters.
+    fn from_read<'de, R: serde_json::de::Read<'de>>(read: R) -> ParsleyResult<Self> {
+        let mut deserializer = serde_json::Deserializer::new(read);
+        let image_config = Self::from_deserializer(&mut deserializer)?;
+
+        deserializer.end()?;
+
+        Ok(image_config)
+    }
+
+    /// Deserializes a configuration out of its JSON, reporting which OCI field, if any, is
+    /// invalid.
+    fn from_value(json: serde_json::Value) -> ParsleyResult<Self> {
+        Self::from_deserializer(&json)
+    }
+
+    /// Deserializes a configuration, reporting which OCI field, if any, is invalid along with the
+    /// error of the deserializer.
+    fn from_deserializer<'de, D>(deserializer: D) -> ParsleyResult<Self>
+    where
+        D: serde::Deserializer<'de, Error = serde_json::Error>,
+    {
+        let invalid_field = Cell::new(None);
+
+        ImageConfigurationSeed(&invalid_field)
+            .deserialize(deserializer)
+            .map_err(|source| match invalid_field.get() {
+                Some(field) => ParsleyError::OciWithContext {
+                    field: field.to_owned(),
+                    source,
+                },
+                None => source.into(),
+            })
+    }
+
+    /// Attempts to write an image configuration to a JSON string whose healthcheck durations are
+    /// encoded in the given format.
+    ///
+    /// Sub-second precision is lost when writing durations as seconds.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let s = image_config
+    ///     .to_string_with_duration_format(image::DurationFormat::Seconds)
+    ///     .unwrap();
+    /// ```
+    pub fn to_string_with_duration_format(&self, format: DurationFormat) -> ParsleyResult<String> {
+        let mut json = serde_json::to_value(self)?;
+
+        convert_healthcheck_durations(&mut json, |nanos| Ok(format.from_nanos(nanos)))?;
+
+        Ok(serde_json::to_string(&json)?)
+    }
+
+    /// Attempts to write an image configuration to a file as JSON. If the file already exists, it
+    /// will be overwritten.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file cannot be created or written
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// image_config.to_file("my-config.json").unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> ParsleyResult<()> {
+        util::json::to_file(self, path)
+    }
+
+    /// Attempts to write an image configuration to a stream as JSON.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be written
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let mut writer = Vec::new();
+    /// image_config.to_writer(&mut writer).unwrap();
+    /// ```
+    pub fn to_writer<W: Write>(&self, writer: W) -> ParsleyResult<()> {
+        util::json::to_writer(self, writer)
+    }
+
+    /// Serializes the image configuration into its canonical form: compact JSON with the keys of
+    /// every object sorted, such that the same configuration always yields the same bytes.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let bytes = image_config.to_canonical_vec().unwrap();
+    /// ```
+    pub fn to_canonical_vec(&self) -> ParsleyResult<Vec<u8>> {
+        util::json::to_canonical_vec(self)
+    }
+
+    /// Computes the `sha256:...` digest of the configuration as written by this crate, e.g. to
+    /// store it in a content addressable storage.
+    ///
+    /// The digest is computed over the [canonical form](ImageConfiguration::to_canonical_vec) of
+    /// the configuration. It thus equals the digest of any blob written by
+    /// [to_file](ImageConfiguration::to_file) or [to_writer](ImageConfiguration::to_writer), but
+    /// not necessarily the digest of a blob produced by another tool with a different key order or
+    /// whitespace, for which [digest_of_slice](ImageConfiguration::digest_of_slice) is to be used.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let digest = image_config.digest().unwrap();
+    /// ```
+    pub fn digest(&self) -> ParsleyResult<String> {
+        Ok(Self::digest_of_slice(&self.to_canonical_vec()?))
+    }
+
+    /// Computes the `sha256:...` digest of a configuration blob, as Docker and registries
+    /// reference it (e.g. the `<hex>.json` name within a `docker save` archive).
+    ///
+    /// The digest is computed over the raw bytes of the blob, which must thus be kept around:
+    /// serializing a deserialized configuration is not guaranteed to yield the original bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// assert_eq!(
+    ///     image::ImageConfiguration::digest_of_slice(b""),
+    ///     "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    /// );
+    /// ```
+    pub fn digest_of_slice(v: &[u8]) -> String {
+        docker::digest::Digest::sha256(v).to_string()
+    }
+
+    /// Computes the `sha256:...` digest of a configuration file, as Docker and registries
+    /// reference it.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file does not exist or cannot be read.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let digest = image::ImageConfiguration::digest_of_file("config.json").unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn digest_of_file<P: AsRef<Path>>(path: P) -> ParsleyResult<String> {
+        Ok(Self::digest_of_slice(&std::fs::read(path)?))
+    }
+
+    /// The labels of the image, ordered by key. Empty if the configuration has no labels.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    ///
+    /// for (key, value) in image_config.labels() {
+    ///     println!("{key}={value}");
+    /// }
+    /// ```
+    pub fn labels(&self) -> BTreeMap<&str, &str> {
+        self.oci_spec
+            .config()
+            .iter()
+            .filter_map(|config| config.labels().as_ref())
+            .flatten()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+
+    /// Retrieves the value of a label.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let maintainer = image_config.label("maintainer");
+    /// ```
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.oci_spec
+            .config()
+            .as_ref()
+            .and_then(|config| config.labels().as_ref())
+            .and_then(|labels| labels.get(key))
+            .map(String::as_str)
+    }
+
+    /// The exposed ports of the image, ordered by port then protocol, a port without protocol
+    /// being a TCP one (e.g. `5432/tcp` and `5432` both become `(5432, Protocol::Tcp)`).
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if a port is not shaped as `port[/protocol]`.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    ///
+    /// for (port, protocol) in image_config.exposed_ports_parsed().unwrap() {
+    ///     println!("{port}/{protocol}");
+    /// }
+    /// ```
+    pub fn exposed_ports_parsed(&self) -> ParsleyResult<Vec<(u16, Protocol)>> {
+        let mut ports = self
+            .oci_spec
+            .config()
+            .iter()
+            .filter_map(|config| config.exposed_ports().as_ref())
+            .flatten()
+            .map(|port| parse_exposed_port(port))
+            .collect::<ParsleyResult<Vec<_>>>()?;
+
+        ports.sort();
+
+        Ok(ports)
+    }
+
+    /// The operating system version the image requires (`os.version`), e.g. the build of a
+    /// Windows base image such as `10.0.20348.2113`.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    ///
+    /// if let Some(os_version) = image_config.os_version() {
+    ///     println!("requires {os_version}");
+    /// }
+    /// ```
+    pub fn os_version(&self) -> Option<&str> {
+        self.oci_spec.os_version().as_deref()
+    }
+
+    /// The operating system features the image requires (`os.features`), e.g. `win32k` for
+    /// Windows images. Empty if none are recorded.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let requires_win32k = image_config
+    ///     .os_features()
+    ///     .iter()
+    ///     .any(|feature| feature == "win32k");
+    /// ```
+    pub fn os_features(&self) -> &[String] {
+        self.oci_spec.os_features().as_deref().unwrap_or_default()
+    }
+
+    /// The platform the image runs on, assembled from the `os`, `architecture` and `variant` of
+    /// the configuration.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let platform = image_config.platform();
+    ///
+    /// println!("{}/{}", platform.os(), platform.architecture());
+    /// ```
+    pub fn platform(&self) -> oci_spec::image::Platform {
+        let mut platform = oci_spec::image::Platform::default();
+
+        platform.set_os(self.oci_spec.os().clone());
+        platform.set_architecture(self.oci_spec.architecture().clone());
+        platform.set_variant(self.oci_spec.variant().clone());
+
+        platform
+    }
+
+    /// Stamps the architecture the image is built for, e.g. when cross building. The variant is
+    /// left untouched, use [set_variant](ImageConfiguration::set_variant) to keep it consistent
+    /// with the new architecture.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    /// use oci_spec::image::Arch;
+    ///
+    /// let mut image_config = image::ImageConfiguration::default();
+    /// image_config.set_architecture(Arch::ARM64).set_variant(Some("v8".to_owned()));
+    ///
+    /// assert_eq!(image_config.platform().architecture(), &Arch::ARM64);
+    /// ```
+    pub fn set_architecture(&mut self, architecture: oci_spec::image::Arch) -> &mut Self {
+        self.oci_spec.set_architecture(architecture);
+        self
+    }
+
+    /// Stamps the operating system the image is built for, e.g. when cross building.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    /// use oci_spec::image::Os;
+    ///
+    /// let mut image_config = image::ImageConfiguration::default();
+    /// image_config.set_os(Os::Windows);
+    ///
+    /// assert_eq!(image_config.platform().os(), &Os::Windows);
+    /// ```
+    pub fn set_os(&mut self, os: oci_spec::image::Os) -> &mut Self {
+        self.oci_spec.set_os(os);
+        self
+    }
+
+    /// Stamps the variant of the architecture the image is built for (e.g. `v8` for `arm64`), or
+    /// clears it with `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let mut image_config = image::ImageConfiguration::default();
+    /// image_config.set_variant(Some("v8".to_owned()));
+    ///
+    /// assert_eq!(image_config.platform().variant().as_deref(), Some("v8"));
+    /// ```
+    pub fn set_variant(&mut self, variant: Option<String>) -> &mut Self {
+        self.oci_spec.set_variant(variant);
+        self
+    }
+
+    /// Checks whether the image can run on a host platform, reporting the first mismatch
+    /// otherwise.
+    ///
+    /// Operating systems and architectures must be equal, while a missing variant, either of the
+    /// image or of the host, is compatible with any variant of the same architecture, as container
+    /// runtimes do.
+    ///
+    /// # Errors
+    /// The [PlatformMismatch](PlatformMismatch) making the image incompatible with the host.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::{Arch, Os, PlatformBuilder};
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let host = PlatformBuilder::default()
+    ///     .os(Os::Linux)
+    ///     .architecture(Arch::Amd64)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// if let Err(mismatch) = image_config.is_compatible_with(&host) {
+    ///     println!("cannot run image: {mismatch}");
+    /// }
+    /// ```
+    pub fn is_compatible_with(
+        &self,
+        host: &oci_spec::image::Platform,
+    ) -> Result<(), PlatformMismatch> {
+        if self.oci_spec.os() != host.os() {
+            return Err(PlatformMismatch::Os {
+                image: self.oci_spec.os().clone(),
+                host: host.os().clone(),
+            });
+        }
+
+        if self.oci_spec.architecture() != host.architecture() {
+            return Err(PlatformMismatch::Architecture {
+                image: self.oci_spec.architecture().clone(),
+                host: host.architecture().clone(),
+            });
+        }
+
+        match (self.oci_spec.variant(), host.variant()) {
+            (Some(variant), Some(host_variant)) if variant != host_variant => {
+                Err(PlatformMismatch::Variant {
+                    image: variant.clone(),
+                    host: host_variant.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Lists the values that differ between two configurations, ordered by path.
+    ///
+    /// The comparison runs over the serialized configurations, thus covering both the OCI and
+    /// Docker fields: objects are compared key by key and arrays index by index, such that every
+    /// difference names a leaf value, or a value present in a single configuration.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if a configuration cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let old = image::ImageConfiguration::from_file("old.json").unwrap();
+    /// let new = image::ImageConfiguration::from_file("new.json").unwrap();
+    ///
+    /// for difference in old.diff(&new).unwrap() {
+    ///     println!("{}: {:?} -> {:?}", difference.path(), difference.old(), difference.new());
+    /// }
+    /// ```
+    pub fn diff(&self, other: &Self) -> ParsleyResult<Vec<ConfigDifference>> {
+        let mut differences = Vec::new();
+
+        diff_values(
+            "",
+            Some(&serde_json::to_value(self)?),
+            Some(&serde_json::to_value(other)?),
+            &mut differences,
+        );
+
+        Ok(differences)
+    }
+
+    /// Whether two configurations are equal once their `created` timestamps, of the image and of
+    /// every history entry, are left out, as for images rebuilt reproducibly at different times.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let built = image::ImageConfiguration::from_file("built.json").unwrap();
+    /// let rebuilt = image::ImageConfiguration::from_file("rebuilt.json").unwrap();
+    ///
+    /// assert!(built.eq_ignoring_timestamps(&rebuilt), "build is not reproducible");
+    /// ```
+    pub fn eq_ignoring_timestamps(&self, other: &Self) -> bool {
+        self.without_timestamps() == other.without_timestamps()
+    }
+
+    /// Copy of the configuration whose `created` timestamps are all unset.
+    fn without_timestamps(&self) -> Self {
+        let mut config = self.clone();
+
+        config.oci_spec.set_created(None);
+
+        for entry in config.oci_spec.history_mut() {
+            entry.set_created(None);
+        }
+
+        config
+    }
+
+    /// Calls `f` with every field of the serialized configuration and its value, in the order of
+    /// the keys, thus covering both the OCI and Docker fields.
+    ///
+    /// Fields are named by their dotted path (e.g. `config.Env`, `rootfs.diff_ids`). Objects are
+    /// visited before their own fields, while arrays are visited as a whole.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    ///
+    /// image_config
+    ///     .for_each_field(|path, value| println!("{path}: {value}"))
+    ///     .unwrap();
+    /// ```
+    pub fn for_each_field<F>(&self, mut f: F) -> ParsleyResult<()>
+    where
+        F: FnMut(&str, &serde_json::Value),
+    {
+        visit_fields("", &serde_json::to_value(self)?, &mut f);
+
+        Ok(())
+    }
+
+    /// The environment variables the image runs with, parsed from the `KEY=VALUE` entries of the
+    /// configuration.
+    ///
+    /// Later entries override earlier ones with the same key, while entries without `=` are kept
+    /// with an empty value.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let path = image_config.effective_env().get("PATH").cloned();
+    /// ```
+    pub fn effective_env(&self) -> BTreeMap<String, String> {
+        self.oci_spec
+            .config()
+            .as_ref()
+            .and_then(|config| config.env().as_ref())
+            .into_iter()
+            .flatten()
+            .map(|entry| match entry.split_once('=') {
+                Some((key, value)) => (key.to_owned(), value.to_owned()),
+                None => (entry.clone(), String::default()),
+            })
+            .collect()
+    }
+
+    /// The command the image runs, combining `Entrypoint` and `Cmd` as Docker does.
+    ///
+    /// On Windows images whose `ArgsEscaped` is set, the first argument is a command line escaped
+    /// at build time, thus it is kept as is while the following arguments are escaped and
+    /// appended to it, yielding a [CommandLine::Escaped](CommandLine::Escaped). Everywhere else,
+    /// `ArgsEscaped` is ignored and the arguments are concatenated into a
+    /// [CommandLine::Args](CommandLine::Args).
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image::{self, CommandLine};
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    ///
+    /// match image_config.entrypoint_and_cmd() {
+    ///     CommandLine::Args(args) => println!("{args:?}"),
+    ///     CommandLine::Escaped(command_line) => println!("{command_line}"),
+    /// }
+    /// ```
+    pub fn entrypoint_and_cmd(&self) -> CommandLine {
+        let config = self.oci_spec.config().as_ref();
+        let args = config
+            .and_then(|config| config.entrypoint().as_ref())
+            .into_iter()
+            .chain(config.and_then(|config| config.cmd().as_ref()))
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        let args_escaped = self
+            .docker_oci_extension
+            .as_ref()
+            .and_then(|extension| extension.config.as_ref())
+            .map_or(false, |config| config.args_escaped);
+
+        match args.split_first() {
+            Some((command_line, rest))
+                if args_escaped && *self.oci_spec.os() == oci_spec::image::Os::Windows =>
+            {
+                CommandLine::Escaped(
+                    std::iter::once(command_line.clone())
+                        .chain(rest.iter().map(|arg| escape_windows_arg(arg)))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+            }
+            _ => CommandLine::Args(args),
+        }
+    }
+
+    /// The creation time of the image, parsed out of the `created` RFC 3339 timestamp, which is
+    /// stored as is. `None` if the configuration has no timestamp.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the `created` timestamp is not in the
+    /// RFC 3339 format.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    ///
+    /// if let Some(created_at) = image_config.created_at().unwrap() {
+    ///     println!("built in {}", created_at.year());
+    /// }
+    /// ```
+    pub fn created_at(&self) -> ParsleyResult<Option<OffsetDateTime>> {
+        self.oci_spec
+            .created()
+            .as_ref()
+            .map(|created| {
+                parse_timestamp(created).ok_or_else(|| {
+                    docker::Error::from(Error::InvalidCreatedTimestamp(created.clone())).into()
+                })
+            })
+            .transpose()
+    }
+
+    /// The creation times of the history entries, in the order of the entries, parsed out of
+    /// their `created` RFC 3339 timestamps. `None` for the entries without a timestamp.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if a `created` timestamp is not in the RFC 3339
+    /// format.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let history = image_config.oci_spec().history();
+    ///
+    /// for (entry, created_at) in history.iter().zip(image_config.history_created_at().unwrap()) {
+    ///     println!("{:?} at {created_at:?}", entry.created_by());
+    /// }
+    /// ```
+    pub fn history_created_at(&self) -> ParsleyResult<Vec<Option<OffsetDateTime>>> {
+        self.oci_spec
+            .history()
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                entry
+                    .created()
+                    .as_ref()
+                    .map(|created| {
+                        parse_timestamp(created).ok_or_else(|| {
+                            docker::Error::from(Error::InvalidHistoryTimestamp {
+                                index,
+                                created: created.clone(),
+                            })
+                            .into()
+                        })
+                    })
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// The history entries of the image in chronological order, according to their `created`
+    /// timestamps.
+    ///
+    /// The sort is stable, thus entries created at the same time keep their relative order, while
+    /// entries without a timestamp come first.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if a `created` timestamp is not in the RFC 3339
+    /// format.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    ///
+    /// for entry in image_config.history_sorted_by_created().unwrap() {
+    ///     println!("{:?}", entry.created_by());
+    /// }
+    /// ```
+    pub fn history_sorted_by_created(&self) -> ParsleyResult<Vec<&oci_spec::image::History>> {
+        let mut history = self
+            .history_created_at()?
+            .into_iter()
+            .zip(self.oci_spec.history())
+            .collect::<Vec<_>>();
+
+        history.sort_by_key(|(created, _)| *created);
+
+        Ok(history.into_iter().map(|(_, entry)| entry).collect())
+    }
+
+    /// Records a build step, appending its history entry and, unless the step is an empty layer,
+    /// its diff id to the `rootfs`, such that both stay consistent.
+    ///
+    /// Empty layers (e.g. `ENV` or `CMD` steps) do not add a layer to the `rootfs`, thus their
+    /// `diff_id` is ignored.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the diff id of a non empty layer is not a
+    /// valid digest.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let mut image_config = image::ImageConfiguration::default();
+    ///
+    /// image_config
+    ///     .push_history_layer(
+    ///         "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    ///         "/bin/sh -c #(nop) ADD file:bc58956fa3d1 in / ",
+    ///         false,
+    ///     )
+    ///     .unwrap();
+    /// image_config
+    ///     .push_history_layer("", "/bin/sh -c #(nop)  CMD [\"bash\"]", true)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(image_config.oci_spec().rootfs().diff_ids().len(), 1);
+    /// assert_eq!(image_config.oci_spec().history().len(), 2);
+    /// ```
+    pub fn push_history_layer<D, C>(
+        &mut self,
+        diff_id: D,
+        created_by: C,
+        empty_layer: bool,
+    ) -> ParsleyResult<()>
+    where
+        D: Into<String>,
+        C: Into<String>,
+    {
+        let mut history = oci_spec::image::HistoryBuilder::default().created_by(created_by);
+
+        if empty_layer {
+            history = history.empty_layer(true);
+        } else {
+            let diff_id = diff_id.into();
+
+            docker::digest::Digest::from_str(&diff_id)?;
+            self.oci_spec.rootfs_mut().diff_ids_mut().push(diff_id);
+        }
+
+        self.oci_spec.history_mut().push(history.build()?);
+
+        Ok(())
+    }
+
+    /// The chain ids of the layers, in order, as derived from the `diff_ids` by Docker and the OCI
+    /// specification to identify a layer along with every layer below it.
+    ///
+    /// The chain id of the first layer is its diff id, while the chain id of every following
+    /// layer is `sha256(<chain id of the previous layer> + " " + <diff id of the layer>)`.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let top_layer_chain_id = image_config.chain_ids().pop();
+    /// ```
+    pub fn chain_ids(&self) -> Vec<String> {
+        let mut chain_ids: Vec<String> = Vec::new();
+
+        for diff_id in self.oci_spec.rootfs().diff_ids() {
+            let chain_id = match chain_ids.last() {
+                Some(parent) => {
+                    docker::digest::Digest::sha256(format!("{parent} {diff_id}").as_bytes())
+                        .to_string()
+                }
+                None => diff_id.clone(),
+            };
+
+            chain_ids.push(chain_id);
+        }
+
+        chain_ids
+    }
+
+    /// Checks that a manifest item lists as many layers as the `diff_ids` recorded in the `rootfs`
+    /// of the configuration, a mismatch denoting a corrupt image.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the number of layers differs from the number
+    /// of diff ids.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+    /// let image_config = image_manifest.0[0].load_configuration(".").unwrap();
+    ///
+    /// image_config.assert_layer_count(&image_manifest.0[0]).unwrap();
+    /// ```
+    pub fn assert_layer_count(
+        &self,
+        manifest_item: &docker::image::ManifestItem,
+    ) -> ParsleyResult<()> {
+        let expected = self.oci_spec.rootfs().diff_ids().len();
+        let actual = manifest_item.layer_count();
+
+        if expected != actual {
+            return Err(docker::Error::from(Error::LayerCountMismatch { expected, actual }).into());
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the uncompressed layers, in order, hash to the `diff_ids` recorded in the
+    /// `rootfs` of the configuration.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if a layer cannot be read
+    /// [ParsleyError::Docker](ParsleyError::Docker) if a layer digest does not match its diff id
+    /// or the number of layers differs from the number of diff ids.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use std::fs::File;
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+    /// let layers = ["layer1.tar", "layer2.tar"].map(|path| File::open(path).unwrap());
+    ///
+    /// image_config.verify_diff_ids(layers.into_iter()).unwrap();
+    /// ```
+    pub fn verify_diff_ids(&self, layers: impl Iterator<Item = impl Read>) -> ParsleyResult<()> {
+        let diff_ids = self.oci_spec.rootfs().diff_ids();
+        let mut count = 0;
+
+        for (index, mut layer) in layers.enumerate() {
+            count += 1;
+
+            // Extra layers are only counted, the mismatch is reported below
+            let expected = match diff_ids.get(index) {
+                Some(expected) => expected,
+                None => continue,
+            };
+            let actual = docker::layer::hash_reader(&mut layer)?;
+
+            if *expected != actual {
+                return Err(docker::Error::from(Error::DiffIdMismatch {
+                    index,
+                    expected: expected.clone(),
+                    actual,
+                })
+                .into());
+            }
+        }
+
+        if count != diff_ids.len() {
+            return Err(docker::Error::from(Error::LayerCountMismatch {
+                expected: diff_ids.len(),
+                actual: count,
+            })
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// HealthcheckConfig holds configuration settings for the HEALTHCHECK feature.
+///
+/// For more details refer to the [Go Spec](https://github.com/moby/moby/blob/f6fa56194f1c9cfd2e4ae41a17f75ab6b04f82df/image/spec/specs-go/v1/image.go#L35).
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use parsley::docker::image;
+///
+/// let check = image::HealthcheckConfigBuilder::default()
+///     .test(vec!["CMD-SHELL".to_owned(), "/usr/bin/check-health localhost".to_owned()])
+///     .interval(Duration::default())
+///     .timeout(Duration::default())
+///     .start_interval(Duration::default())
+///     .retries(u32::default())
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// Building a healthcheck that sets `interval`, `timeout`, `start_interval` or `retries` without
+/// a `test` to run fails with [Error::InvalidHealthcheckConfiguration](Error::InvalidHealthcheckConfiguration).
+#[derive(Builder, Getters, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(validate = "Self::validate", error = "ParsleyError")
+)]
+#[getset(get = "pub")]
+pub struct HealthcheckConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    test: Option<Vec<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "util::json::serialize_duration",
+        deserialize_with = "util::json::deserialize_duration"
+    )]
+    interval: Option<Duration>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "util::json::serialize_duration",
+        deserialize_with = "util::json::deserialize_duration"
+    )]
+    timeout: Option<Duration>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "util::json::serialize_duration",
+        deserialize_with = "util::json::deserialize_duration"
+    )]
+    start_interval: Option<Duration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retries: Option<u32>,
+}
+
+impl HealthcheckConfig {
+    /// Attempts to load a healthcheck from bytes of JSON text, as found under
+    /// `config.Healthcheck`.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the healthcheck cannot be deserialized.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use parsley::docker::image;
+    ///
+    /// let check =
+    ///     image::HealthcheckConfig::from_slice(br#"{"Test": ["NONE"], "Interval": 30000000000}"#)
+    ///         .unwrap();
+    ///
+    /// assert_eq!(check.interval(), &Some(Duration::from_secs(30)));
+    /// ```
+    pub fn from_slice(v: &[u8]) -> ParsleyResult<Self> {
+        util::json::from_slice(v)
+    }
+}
+
+impl FromStr for HealthcheckConfig {
+    type Err = ParsleyError;
+
+    /// Attempts to load a healthcheck from a JSON string, as found under `config.Healthcheck`.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the healthcheck cannot be deserialized.
+    ///
+    /// # Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use parsley::docker::image;
+    ///
+    /// let check = image::HealthcheckConfig::from_str(r#"{"Test": ["CMD", "true"], "Retries": 3}"#)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(check.retries(), &Some(3));
+    /// ```
+    fn from_str(s: &str) -> ParsleyResult<Self> {
+        util::json::from_str(s)
+    }
+}
+
+impl ImageConfigurationBuilder {
+    /// Merges raw JSON fields into the Docker extension, such that fields this crate does not
+    /// model yet can be written, e.g. `{"config": {"NewField": true}}`. Successive calls are
+    /// merged with [merge](crate::merge).
+    ///
+    /// Fields of the OCI specification are ignored when serializing, the values of `oci_spec`
+    /// taking precedence.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the merged fields do not form a valid
+    /// extension, e.g. `extra` is not an object or sets `config.Memory` to a string.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image;
+    ///
+    /// let image_config = image::ImageConfigurationBuilder::default()
+    ///     .oci_spec(oci_spec::image::ImageConfiguration::default())
+    ///     .extra_json(serde_json::json!({"config": {"NewField": true}}))
+    ///     .unwrap()
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(serde_json::to_value(&image_config).unwrap()["config"]["NewField"], true);
+    /// ```
+    pub fn extra_json(mut self, extra: serde_json::Value) -> ParsleyResult<Self> {
+        let mut merged = self
+            .docker_oci_extension
+            .flatten()
+            .unwrap_or_default()
+            .to_value()?;
+
+        util::json::merge(&mut merged, extra);
+        self.docker_oci_extension = Some(Some(ImageConfigurationExtension::from_value(merged)?));
+
+        Ok(self)
+    }
+
+    /// Rejects configurations without OCI specifications, which the defaults cannot stand for.
+    fn validate(&self) -> ParsleyResult<()> {
+        if self.oci_spec.is_none() {
+            return Err(docker::Error::from(Error::MissingRequiredField {
+                structure: "ImageConfiguration".to_owned(),
+                field: "oci_spec".to_owned(),
+            })
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+impl HealthcheckConfigBuilder {
+    /// Rejects healthchecks that set their parameters without a test to run.
+    fn validate(&self) -> ParsleyResult<()> {
+        let has_test = matches!(self.test, Some(Some(ref test)) if !test.is_empty());
+        let has_parameters = matches!(self.interval, Some(Some(_)))
+            || matches!(self.timeout, Some(Some(_)))
+            || matches!(self.start_interval, Some(Some(_)))
+            || matches!(self.retries, Some(Some(_)));
+
+        if has_parameters && !has_test {
+            return Err(docker::Error::from(Error::InvalidHealthcheckConfiguration).into());
+        }
+
+        Ok(())
+    }
+}
+
+/// A value that differs between two image configurations, as reported by
+/// [ImageConfiguration::diff](ImageConfiguration::diff).
+#[derive(Getters, Clone, Debug, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct ConfigDifference {
+    /// Path of the value within the serialized configuration (e.g. `config.Env` or
+    /// `rootfs.diff_ids[2]`).
+    path: String,
+    /// Value of the first configuration, `None` if the path only exists in the second one.
+    old: Option<serde_json::Value>,
+    /// Value of the second configuration, `None` if the path only exists in the first one.
+    new: Option<serde_json::Value>,
+}
+
+/// Command an image runs, as resolved by
+/// [ImageConfiguration::entrypoint_and_cmd](ImageConfiguration::entrypoint_and_cmd).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CommandLine {
+    /// Arguments passed as is to the process, the first one being the executable.
+    Args(Vec<String>),
+    /// Windows command line, already escaped.
+    Escaped(String),
+}
+
+/// Transport protocol of an exposed port.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Protocol {
+    /// TCP, the protocol of ports exposed without one.
+    Tcp,
+    /// UDP.
+    Udp,
+    /// SCTP.
+    Sctp,
+}
+
+impl Protocol {
+    /// The protocol name, as written in exposed ports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tcp => "tcp",
+            Self::Udp => "udp",
+            Self::Sctp => "sctp",
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Reason an image cannot run on a host, as reported by
+/// [ImageConfiguration::is_compatible_with](ImageConfiguration::is_compatible_with).
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+pub enum PlatformMismatch {
+    /// The image targets another operating system.
+    #[error("image os {image} does not match host os {host}")]
+    Os {
+        /// Operating system of the image
+        image: oci_spec::image::Os,
+        /// Operating system of the host
+        host: oci_spec::image::Os,
+    },
+
+    /// The image targets another architecture.
+    #[error("image architecture {image} does not match host architecture {host}")]
+    Architecture {
+        /// Architecture of the image
+        image: oci_spec::image::Arch,
+        /// Architecture of the host
+        host: oci_spec::image::Arch,
+    },
+
+    /// The image targets another variant of the architecture.
+    #[error("image variant {image} does not match host variant {host}")]
+    Variant {
+        /// Variant of the image
+        image: String,
+        /// Variant of the host
+        host: String,
+    },
+}
+
+/// A memory limit of a configuration, which Docker writes as `-1` when unlimited (e.g. for
+/// `MemorySwap`).
+///
+/// # Example
+/// ```
+/// use parsley::docker::image;
+///
+/// let config_extension = image::ConfigExtension::from_slice(br#"{"MemorySwap": -1}"#).unwrap();
+///
+/// assert_eq!(config_extension.memory_swap(), &Some(image::MemoryLimit::Unlimited));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MemoryLimit {
+    /// No limit, written as `-1`.
+    Unlimited,
+    /// Limit in bytes.
+    Bytes(u64),
+}
+
+impl From<u64> for MemoryLimit {
+    fn from(bytes: u64) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+impl Serialize for MemoryLimit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Unlimited => serializer.serialize_i64(-1),
+            Self::Bytes(bytes) => serializer.serialize_u64(*bytes),
+        }
+    }
+}
+
+/// Visitor of a memory limit, either a count of bytes or `-1` for no limit.
+struct MemoryLimitVisitor;
+
+impl<'de> serde::de::Visitor<'de> for MemoryLimitVisitor {
+    type Value = MemoryLimit;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a count of bytes or -1 for no limit")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, bytes: u64) -> Result<Self::Value, E> {
+        Ok(MemoryLimit::Bytes(bytes))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, bytes: i64) -> Result<Self::Value, E> {
+        match bytes {
+            -1 => Ok(MemoryLimit::Unlimited),
+            _ => u64::try_from(bytes)
+                .map(MemoryLimit::Bytes)
+                .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(bytes), &self)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MemoryLimit {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_i64(MemoryLimitVisitor)
+    }
+}
+
+/// Unit in which the integer healthcheck durations of a configuration are encoded.
+///
+/// Docker serializes durations as Go's `time.Duration`, that is an integer count of nanoseconds,
+/// in every version supporting the `HEALTHCHECK` instruction (1.12 onwards). Encodings in seconds
+/// only come from third-party tools producing Docker-like configurations.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DurationFormat {
+    /// Integers are nanoseconds, as written by Docker.
+    #[default]
+    Nanoseconds,
+    /// Integers are seconds.
+    Seconds,
+}
+
+impl DurationFormat {
+    /// Converts a duration encoded in this format to nanoseconds.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the duration overflows a count of
+    /// nanoseconds.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image::DurationFormat;
+    ///
+    /// assert_eq!(DurationFormat::Seconds.to_nanos(30).unwrap(), 30_000_000_000);
+    /// assert!(DurationFormat::Seconds.to_nanos(u64::MAX).is_err());
+    /// ```
+    pub fn to_nanos(&self, value: u64) -> ParsleyResult<u64> {
+        match self {
+            Self::Nanoseconds => Ok(value),
+            Self::Seconds => value
+                .checked_mul(1_000_000_000)
+                .ok_or_else(|| docker::Error::from(Error::DurationOverflow(value)).into()),
+        }
+    }
+
+    /// Converts nanoseconds to a duration encoded in this format.
+    pub fn from_nanos(&self, nanos: u64) -> u64 {
+        match self {
+            Self::Nanoseconds => nanos,
+            Self::Seconds => Duration::from_nanos(nanos).as_secs(),
+        }
+    }
+}
+
+/// Escapes an argument of a Windows command line, following the rules of Go's
+/// `syscall.EscapeArg` used by Docker.
+fn escape_windows_arg(arg: &str) -> String {
+    if arg.is_empty() {
+        return "\"\"".to_owned();
+    }
+
+    let needs_backslash = arg.contains(['"', '\\']);
+    let has_space = arg.contains([' ', '\t']);
+
+    if !needs_backslash && !has_space {
+        return arg.to_owned();
+    }
+
+    if !needs_backslash {
+        return format!("\"{arg}\"");
+    }
+
+    let mut escaped = String::with_capacity(arg.len() + 2);
+    let mut slashes = 0;
+
+    if has_space {
+        escaped.push('"');
+    }
+
+    for c in arg.chars() {
+        match c {
+            '\\' => slashes += 1,
+            '"' => {
+                // Backslashes preceding a quote are doubled, then the quote itself is escaped
+                escaped.push_str(&"\\".repeat(slashes + 1));
+                slashes = 0;
+            }
+            _ => slashes = 0,
+        }
+
+        escaped.push(c);
+    }
+
+    if has_space {
+        escaped.push_str(&"\\".repeat(slashes));
+        escaped.push('"');
+    }
+
+    escaped
+}
+
+/// Parses an RFC 3339 timestamp, keeping its sub-second precision.
+fn parse_timestamp(timestamp: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(timestamp, &Rfc3339).ok()
+}
+
+/// Parses an exposed port shaped as `port[/protocol]`.
+fn parse_exposed_port(port: &str) -> ParsleyResult<(u16, Protocol)> {
+    let invalid = || -> ParsleyError {
+        docker::Error::from(Error::InvalidExposedPort(port.to_owned())).into()
+    };
+    let (number, protocol) = port
+        .split_once('/')
+        .unwrap_or((port, Protocol::Tcp.as_str()));
+    let protocol = match protocol.to_ascii_lowercase().as_str() {
+        "tcp" => Protocol::Tcp,
+        "udp" => Protocol::Udp,
+        "sctp" => Protocol::Sctp,
+        _ => return Err(invalid()),
+    };
+
+    Ok((number.parse().map_err(|_| invalid())?, protocol))
+}
+
+/// Top-level fields of the Docker image format (`image.V1Image`) that are neither part of the OCI
+/// specification nor modeled by [ImageConfigurationExtension](ImageConfigurationExtension).
+const DOCKER_FIELDS: &[&str] = &[
+    "id",
+    "parent",
+    "comment",
+    "container",
+    "container_config",
+    "docker_version",
+    "size",
+];
+
+/// Fields of the Docker container configuration (`container.Config`) that are neither part of the
+/// OCI specification nor modeled by [ConfigExtension](ConfigExtension).
+const DOCKER_CONFIG_FIELDS: &[&str] = &[
+    "Hostname",
+    "Domainname",
+    "AttachStdin",
+    "AttachStdout",
+    "AttachStderr",
+    "Tty",
+    "OpenStdin",
+    "StdinOnce",
+    "Image",
+    "NetworkDisabled",
+    "MacAddress",
+    "StopTimeout",
+];
+
+/// Deserializes an image configuration, recording which top-level OCI field, if any, is invalid,
+/// as the error of the field, passed through as is, does not tell which field it stems from.
+struct ImageConfigurationSeed<'a>(&'a Cell<Option<&'static str>>);
+
+impl ImageConfigurationSeed<'_> {
+    /// Deserializes the value of the OCI `field`, recording the field on failure.
+    fn oci_field<'de, A, T>(&self, map: &mut A, field: &'static str) -> Result<T, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+        T: Deserialize<'de>,
+    {
+        map.next_value().map_err(|err| {
+            self.0.set(Some(field));
+            err
+        })
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ImageConfigurationSeed<'_> {
+    type Value = ImageConfiguration;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for ImageConfigurationSeed<'_> {
+    type Value = ImageConfiguration;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an image configuration")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let (mut created, mut author, mut os_version, mut os_features, mut variant) =
+            (None, None, None, None, None);
+        let (mut architecture, mut os, mut rootfs, mut history) = (None, None, None, None);
+        let mut config: Option<ConfigFields> = None;
+        let mut extra = BTreeMap::new();
+
+        // As for any JSON object, the last occurrence of a key wins
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "created" => created = self.oci_field(&mut map, "created")?,
+                "author" => author = self.oci_field(&mut map, "author")?,
+                "architecture" => architecture = Some(self.oci_field(&mut map, "architecture")?),
+                "os" => os = Some(self.oci_field(&mut map, "os")?),
+                "os.version" => os_version = self.oci_field(&mut map, "os.version")?,
+                "os.features" => os_features = self.oci_field(&mut map, "os.features")?,
+                "variant" => variant = self.oci_field(&mut map, "variant")?,
+                "config" => config = self.oci_field(&mut map, "config")?,
+                "rootfs" => rootfs = Some(self.oci_field(&mut map, "rootfs")?),
+                "history" => history = Some(self.oci_field(&mut map, "history")?),
+                _ => {
+                    let value: serde_json::Value = map.next_value()?;
+
+                    // Null fields are dropped when serializing anyway
+                    if !value.is_null() {
+                        extra.insert(key, value);
+                    }
+                }
+            }
+        }
+
+        let (oci_config, docker_config) = match config {
+            Some(ConfigFields { oci, mut docker }) => {
+                docker.extra.retain(|_, value| !value.is_null());
+                (Some(oci), Some(docker))
+            }
+            None => (None, None),
+        };
+        let mut oci_spec = oci_spec::image::ImageConfiguration::default();
+
+        oci_spec
+            .set_created(created)
+            .set_author(author)
+            .set_architecture(
+                architecture.ok_or_else(|| serde::de::Error::missing_field("architecture"))?,
+            )
+            .set_os(os.ok_or_else(|| serde::de::Error::missing_field("os"))?)
+            .set_os_version(os_version)
+            .set_os_features(os_features)
+            .set_variant(variant)
+            .set_config(oci_config)
+            .set_rootfs(rootfs.ok_or_else(|| serde::de::Error::missing_field("rootfs"))?)
+            .set_history(history.ok_or_else(|| serde::de::Error::missing_field("history"))?);
+
+        let docker_oci_extension = ImageConfigurationExtension {
+            config: docker_config,
+            extra,
+        };
+
+        Ok(ImageConfiguration {
+            // Without any Docker field left, the configuration is a plain OCI one
+            docker_oci_extension: Some(docker_oci_extension)
+                .filter(|extension| *extension != ImageConfigurationExtension::default()),
+            oci_spec,
+        })
+    }
+}
+
+/// Fields of the `config` field of an image configuration, shared by the OCI specification and the
+/// Docker extension.
+#[derive(Deserialize)]
+struct ConfigFields {
+    /// Comes first, such that the fields known to the OCI specification are claimed before the
+    /// Docker extension captures the remaining ones.
+    #[serde(flatten)]
+    oci: oci_spec::image::Config,
+    #[serde(flatten)]
+    docker: ConfigExtension,
+}
+
+/// Removes from a configuration JSON the fields set in `oci_json`, the serialization of its OCI
+/// specification, along with the null fields, which are dropped when serializing anyway.
+///
+/// Only `config` is shared by the OCI specification and the Docker extension, thus it is the only
+/// field stripped key by key rather than as a whole.
+fn strip_oci_fields(json: &mut serde_json::Value, oci_json: &serde_json::Value) {
+    let (json, oci_json) = match (json.as_object_mut(), oci_json.as_object()) {
+        (Some(json), Some(oci_json)) => (json, oci_json),
+        _ => return,
+    };
+
+    for (key, oci_value) in oci_json {
+        match (key.as_str(), json.get_mut(key)) {
+            ("config", Some(serde_json::Value::Object(config))) => {
+                for oci_key in oci_value
+                    .as_object()
+                    .into_iter()
+                    .flat_map(|config| config.keys())
+                {
+                    config.remove(oci_key);
+                }
+
+                config.retain(|_, value| !value.is_null());
+            }
+            _ => {
+                json.remove(key);
+            }
+        }
+    }
+
+    json.retain(|_, value| !value.is_null());
+}
+
+/// Appends to `differences` every leaf value that differs between `old` and `new`, objects being
+/// compared by key and arrays by index.
+fn diff_values(
+    path: &str,
+    old: Option<&serde_json::Value>,
+    new: Option<&serde_json::Value>,
+    differences: &mut Vec<ConfigDifference>,
+) {
+    match (old, new) {
+        (Some(serde_json::Value::Object(old)), Some(serde_json::Value::Object(new))) => {
+            let keys = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+
+            for key in keys {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+
+                diff_values(&path, old.get(key), new.get(key), differences);
+            }
+        }
+        (Some(serde_json::Value::Array(old)), Some(serde_json::Value::Array(new))) => {
+            for index in 0..old.len().max(new.len()) {
+                diff_values(
+                    &format!("{path}[{index}]"),
+                    old.get(index),
+                    new.get(index),
+                    differences,
+                );
+            }
+        }
+        (old, new) if old != new => differences.push(ConfigDifference {
+            path: path.to_owned(),
+            old: old.cloned(),
+            new: new.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+/// Calls `f` with every field of `value` and, recursively, of the objects it holds, naming each
+/// field by its dotted path from `path`.
+fn visit_fields<F>(path: &str, value: &serde_json::Value, f: &mut F)
+where
+    F: FnMut(&str, &serde_json::Value),
+{
+    let fields = match value {
+        serde_json::Value::Object(fields) => fields,
+        _ => return,
+    };
+
+    for (key, value) in fields {
+        let path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+
+        f(&path, value);
+        visit_fields(&path, value, f);
+    }
+}
+
+/// Applies `convert` to every integer duration of the healthcheck within a configuration JSON.
+fn convert_healthcheck_durations<F>(json: &mut serde_json::Value, convert: F) -> ParsleyResult<()>
+where
+    F: Fn(u64) -> ParsleyResult<u64>,
+{
+    let config = match json
+        .get_mut("config")
+        .and_then(|config| config.as_object_mut())
+    {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+
+    // Docker writes `Healthcheck`, while `HealthCheck` is accepted as an alias
+    for key in ["Healthcheck", "HealthCheck"] {
+        let health_check = match config.get_mut(key).and_then(|check| check.as_object_mut()) {
+            Some(health_check) => health_check,
+            None => continue,
+        };
+
+        for field in ["Interval", "Timeout", "StartPeriod", "StartInterval"] {
+            if let Some(value) = health_check.get_mut(field) {
+                if let Some(duration) = value.as_u64() {
+                    *value = convert(duration)?.into();
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oci_spec::image;
+    use std::collections::HashMap;
+    use test_case::test_case;
+
+    fn config() -> ImageConfiguration {
+        let docker_oci_extension = ImageConfigurationExtensionBuilder::default()
+            .config(
+                ConfigExtensionBuilder::default()
+                    .memory(2048_u64)
+                    .memory_swap(4096_u64)
+                    .cpu_shares(8_u16)
+                    .args_escaped(false)
+                    .shell(vec![
+                        "/bin/bash".to_owned(),
+                        "-o".to_owned(),
+                        "pipefail".to_owned(),
+                        "-c".to_owned(),
+                    ])
+                    .on_build(vec!["a".to_owned(), "b".to_owned()])
+                    .health_check(
                         HealthcheckConfigBuilder::default()
                             .test(vec![
                                 "CMD-SHELL".to_owned(),
@@ -325,102 +2364,1667 @@ mod tests {
                             .build()
                             .expect("Build Docker OCI Extension: Healthcheck"),
                     )
+                    .extra(BTreeMap::from([
+                        ("Hostname".to_owned(), "".into()),
+                        ("Domainname".to_owned(), "".into()),
+                        ("AttachStdin".to_owned(), false.into()),
+                        ("AttachStdout".to_owned(), false.into()),
+                        ("AttachStderr".to_owned(), false.into()),
+                        ("Tty".to_owned(), false.into()),
+                        ("OpenStdin".to_owned(), false.into()),
+                        ("StdinOnce".to_owned(), false.into()),
+                        (
+                            "Image".to_owned(),
+                            "sha256:92c6e333aa36edb1932ac4198c20f986e32ad86ebd3c71bac7005ce622a6412c"
+                                .into(),
+                        ),
+                    ]))
+                    .build()
+                    .expect("Build Docker Config Extension"),
+            )
+            .extra(BTreeMap::from([
+                (
+                    "container".to_owned(),
+                    "850331b21eb942e9dad178c06146950af0dbe9b0794213e3fa9306637db74286".into(),
+                ),
+                ("docker_version".to_owned(), "20.10.23".into()),
+            ]))
+            .build()
+            .expect("Docker OCI Image Extension");
+        let oci_spec = image::ImageConfigurationBuilder::default()
+            .created("2023-08-16T06:40:57.929475525Z")
+            .author("author".to_owned())
+            .architecture(image::Arch::ARM64)
+            .os(image::Os::Linux)
+            .config(image::ConfigBuilder::default()
+                .user("1001".to_owned())
+                .exposed_ports(vec!["5432/tcp".to_owned()])
+                .env(vec![
+                    "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin:/usr/lib/postgresql/15/bin".to_owned(),
+                    "GOSU_VERSION=1.16".to_owned(),
+                    "LANG=en_US.utf8".to_owned(),
+                    "PG_MAJOR=15".to_owned(),
+                    "PG_VERSION=15.4-1.pgdg120+1".to_owned(),
+                    "PGDATA=/var/lib/postgresql/data".to_owned()
+                ])
+                .entrypoint(vec![
+                    "docker-entrypoint.sh".to_owned()
+                ])
+                .labels(HashMap::from_iter([("maintainer".to_owned(),"someone".to_owned())]))
+                .stop_signal("SIGINT".to_owned())
+                .cmd(
+                    vec!["postgres".to_owned()]
+                )
+                .volumes(vec!["/var/lib/postgresql/data".to_owned()])
+                .working_dir("/postgres".to_owned())
+                .build()
+                .expect("Build Config")
+            )
+            .rootfs(image::RootFsBuilder::default()
+                .typ("layers")
+                .diff_ids(vec![
+                    "sha256:1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1".to_owned(),
+                    "sha256:310729fcb068da6941441d9627a3d8979e7dbd015c220324331e34af28b7e20c".to_owned(),
+                    "sha256:6cc6868915f4c4d399ec0026fd321acfd0b92e84cd2a51076e89041b3e3118b6".to_owned()
+                ]).build().expect("Rootfs"))
+            .history(vec![
+                image::HistoryBuilder::default()
+                    .created("2023-08-15T23:39:57.178505081Z".to_owned())
+                    .created_by("/bin/sh -c #(nop) ADD file:bc58956fa3d1aff2efb0264655d039fedfff28dc4ff19a65a235e82754ee1cfa in / ".to_owned())
+                    .build()
+                    .expect("Build History 1"),
+                image::HistoryBuilder::default()
+                    .created("2023-08-15T23:39:57.574431303Z".to_owned())
+                    .created_by("/bin/sh -c #(nop)  CMD [\"bash\"]".to_owned())
+                    .empty_layer(true)
+                    .build()
+                    .expect("Build History 2"),
+                image::HistoryBuilder::default()
+                    .created("2023-08-16T06:38:58.796057889Z".to_owned())
+                    .created_by("/bin/sh -c set -eux; \tgroupadd -r postgres --gid=999; \tuseradd -r -g postgres --uid=999 --home-dir=/var/lib/postgresql --shell=/bin/bash postgres; \tmkdir -p /var/lib/postgresql; \tchown -R postgres:postgres /var/lib/postgresql".to_owned())
+                    .build()
+                    .expect("Build History 3")
+            ])
+            .variant("v8".to_owned())
+            .build()
+            .expect("OCI Config Spec");
+
+        ImageConfigurationBuilder::default()
+            .docker_oci_extension(docker_oci_extension)
+            .oci_spec(oci_spec)
+            .build()
+            .expect("Image Config")
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn deserialize() {
+        let config_path = docker::tests::test_data_path("config.json");
+        let deserialized_config =
+            ImageConfiguration::from_file(config_path).expect("Could not deserialize from file");
+
+        assert_eq!(
+            deserialized_config,
+            config(),
+            "Deserialized config does not match expected one"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn from_reader_limited() {
+        let content = std::fs::read(docker::tests::test_data_path("config.json"))
+            .expect("Could not read fixture");
+        let size = content.len() as u64;
+
+        assert_eq!(
+            ImageConfiguration::from_reader_limited(content.as_slice(), size)
+                .expect("Could not deserialize within limit"),
+            config()
+        );
+
+        let result = ImageConfiguration::from_reader_limited(content.as_slice(), size - 1);
+
+        assert!(
+            matches!(result, Err(ParsleyError::SizeLimitExceeded(limit)) if limit == size - 1),
+            "Oversized content was not rejected: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn from_reader() {
+        let content = std::fs::read(docker::tests::test_data_path("config.json"))
+            .expect("Could not read config fixture");
+        let deserialized_config = ImageConfiguration::from_reader(std::io::Cursor::new(content))
+            .expect("Could not deserialize from reader");
+
+        assert_eq!(
+            deserialized_config,
+            config(),
+            "Deserialized config does not match expected one"
+        );
+    }
+
+    #[cfg(all(feature = "fs", feature = "gzip"))]
+    #[test]
+    fn from_gzip_file() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let gzip_path = dir.path().join("config.json.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&gzip_path).expect("Could not create gzip fixture"),
+            flate2::Compression::default(),
+        );
+
+        encoder
+            .write_all(
+                &std::fs::read(docker::tests::test_data_path("config.json"))
+                    .expect("Could not read config fixture"),
+            )
+            .expect("Could not compress config fixture");
+        encoder.finish().expect("Could not compress config fixture");
+
+        assert_eq!(
+            ImageConfiguration::from_gzip_file(&gzip_path).expect("Could not deserialize"),
+            config(),
+            "Deserialized config does not match expected one"
+        );
+        assert_eq!(
+            ImageConfiguration::from_gzip_slice(
+                &std::fs::read(&gzip_path).expect("Could not read gzip fixture")
+            )
+            .expect("Could not deserialize"),
+            config(),
+            "Deserialized config does not match expected one"
+        );
+    }
+
+    #[cfg(all(feature = "fs", feature = "gzip"))]
+    #[test]
+    fn from_gzip_slice_not_gzip() {
+        let content = std::fs::read(docker::tests::test_data_path("config.json"))
+            .expect("Could not read config fixture");
+        let result = ImageConfiguration::from_gzip_slice(&content);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::NotGzipCompressed
+                )))
+            ),
+            "Uncompressed content was not rejected: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test_case("rootfs", serde_json::json!("broken"); "RootFs")]
+    #[test_case("history", serde_json::json!({"created_by": "sh"}); "History")]
+    #[test_case("config", serde_json::json!({"Env": "PATH=/bin"}); "Config")]
+    fn invalid_oci_field(field: &str, value: serde_json::Value) {
+        let mut json: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(docker::tests::test_data_path("config.json"))
+                .expect("Could not read config fixture"),
+        )
+        .expect("Invalid config fixture");
+
+        json[field] = value;
+
+        let result = ImageConfiguration::from_str(&json.to_string());
+
+        assert!(
+            matches!(result, Err(ParsleyError::OciWithContext { field: ref failed, .. }) if failed == field),
+            "Invalid {field} was not reported: {result:?}"
+        );
+        assert!(
+            result
+                .as_ref()
+                .expect_err("Invalid config was accepted")
+                .to_string()
+                .contains(field),
+            "Error does not mention {field}"
+        );
+
+        let err = serde_json::from_str::<ImageConfiguration>(&json.to_string())
+            .expect_err("Invalid config was accepted");
+
+        assert!(
+            matches!(result, Err(ParsleyError::OciWithContext { ref source, .. }) if source.to_string() == err.to_string()),
+            "Serde error of {field} was not passed through: {err}"
+        );
+    }
+
+    #[test]
+    fn error_position() {
+        let oci_error = r#"{
+  "os": "linux",
+  "architecture": 42,
+  "rootfs": {}
+}"#;
+        let result = ImageConfiguration::from_str(oci_error);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::OciWithContext { ref field, ref source })
+                    if field == "architecture" && source.line() == 3
+            ),
+            "Position of the invalid OCI field was not reported: {result:?}"
+        );
+
+        let extension_error = r#"{
+  "os": "linux",
+  "architecture": "amd64",
+  "rootfs": {"type": "layers", "diff_ids": []},
+  "history": [],
+  "config": {
+    "Memory": "lots"
+  }
+}"#;
+        let result = ImageConfiguration::from_slice(extension_error.as_bytes());
+
+        // The fields of config are split between the OCI specification and the Docker extension
+        // once config is read, thus their errors are positioned at its end
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::OciWithContext { ref field, ref source })
+                    if field == "config" && source.line() == 8
+            ),
+            "Position of the invalid extension field was not reported: {result:?}"
+        );
+
+        let result = ImageConfiguration::from_reader(oci_error.as_bytes());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::OciWithContext { ref source, .. }) if source.line() == 3
+            ),
+            "Position of the invalid field was not reported when streaming: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test_case(&["config", "Memmory"], "config.Memmory"; "Misspelled config field")]
+    #[test_case(&["docker_verison"], "docker_verison"; "Misspelled top level field")]
+    fn from_str_strict(path: &[&str], expected: &str) {
+        let content = std::fs::read_to_string(docker::tests::test_data_path("config.json"))
+            .expect("Could not read config fixture");
+        let mut json: serde_json::Value =
+            serde_json::from_str(&content).expect("Invalid config fixture");
+
+        ImageConfiguration::from_str_strict(&content).expect("Docker fields were rejected");
+
+        match path {
+            [field] => json[field] = serde_json::json!("typo"),
+            [parent, field] => json[parent][field] = serde_json::json!(2048),
+            _ => unreachable!(),
+        }
+
+        let s = json.to_string();
+        let result = ImageConfiguration::from_str_strict(&s);
+
+        assert!(
+            ImageConfiguration::from_str(&s).is_ok(),
+            "Lenient parsing rejected unknown field"
+        );
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::UnknownField(ref field)
+                ))) if field == expected
+            ),
+            "Unknown field was not rejected: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn unknown_fields() {
+        let mut json: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(docker::tests::test_data_path("config.json"))
+                .expect("Could not read config fixture"),
+        )
+        .expect("Invalid config fixture");
+
+        json["SomeNewField"] = serde_json::json!({"k": "v"});
+        json["config"]["SomeNewField"] = serde_json::json!([1, 2]);
+
+        let deserialized_config: ImageConfiguration =
+            serde_json::from_value(json.clone()).expect("Could not deserialize");
+        let extension = deserialized_config
+            .docker_oci_extension()
+            .as_ref()
+            .expect("Missing extension");
+
+        assert_eq!(
+            extension.extra().get("SomeNewField"),
+            Some(&json["SomeNewField"]),
+            "Unknown top level field was not captured"
+        );
+        assert_eq!(
+            extension
+                .config()
+                .as_ref()
+                .expect("Missing config extension")
+                .extra()
+                .get("SomeNewField"),
+            Some(&json["config"]["SomeNewField"]),
+            "Unknown config field was not captured"
+        );
+        assert!(
+            !extension.extra().contains_key("architecture"),
+            "OCI field was captured as unknown"
+        );
+
+        let serialized = serde_json::to_value(&deserialized_config).expect("Failed to serialize");
+
+        assert_eq!(serialized["SomeNewField"], json["SomeNewField"]);
+        assert_eq!(
+            serialized["config"]["SomeNewField"],
+            json["config"]["SomeNewField"]
+        );
+        assert_eq!(
+            serde_json::from_value::<ImageConfiguration>(serialized)
+                .expect("Could not deserialize from serialization"),
+            deserialized_config,
+            "Unknown fields do not round trip"
+        );
+    }
+
+    #[test]
+    fn conflicting_keys() {
+        let mut original_config = config();
+        let extension = original_config
+            .docker_oci_extension_mut()
+            .as_mut()
+            .expect("Missing extension");
+
+        extension
+            .extra
+            .insert("architecture".to_owned(), "amd64".into());
+        extension
+            .config
+            .as_mut()
+            .expect("Missing config extension")
+            .extra
+            .insert("User".to_owned(), "root".into());
+
+        let serialized = serde_json::to_value(&original_config).expect("Failed to serialize");
+
+        assert_eq!(
+            serialized["architecture"], "arm64",
+            "Extension overrode OCI"
+        );
+        assert_eq!(
+            serialized["config"]["User"], "1001",
+            "Extension overrode OCI"
+        );
+
+        let deserialized_config: ImageConfiguration =
+            serde_json::from_value(serialized).expect("Could not deserialize");
+        let extension = deserialized_config
+            .docker_oci_extension()
+            .as_ref()
+            .expect("Missing extension");
+
+        assert_eq!(deserialized_config.oci_spec(), original_config.oci_spec());
+        assert!(!extension.extra().contains_key("architecture"));
+        assert!(!extension
+            .config()
+            .as_ref()
+            .expect("Missing config extension")
+            .extra()
+            .contains_key("User"));
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn try_from() {
+        let path = docker::tests::test_data_path("config.json");
+        let content = std::fs::read_to_string(&path).expect("Could not read fixture");
+        let expected = config();
+
+        assert_eq!(
+            ImageConfiguration::try_from(content.as_bytes()).expect("Could not convert from bytes"),
+            expected
+        );
+        assert_eq!(
+            ImageConfiguration::try_from(content.as_str()).expect("Could not convert from string"),
+            expected
+        );
+        assert_eq!(
+            ImageConfiguration::try_from(path.as_path()).expect("Could not convert from path"),
+            expected
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn serde() {
+        let config_path = docker::tests::test_data_path("config.json");
+        let deserialized_config = ImageConfiguration::from_file(config_path)
+            .expect("Could not deserialize from file {config_path}");
+        let serialized_config =
+            serde_json::to_string(&deserialized_config).expect("Failed to serialize");
+        let re_deserialized_config = ImageConfiguration::from_str(&serialized_config)
+            .expect("Could not deserialize from serialization");
+
+        assert_eq!(
+            deserialized_config, re_deserialized_config,
+            "Deserialized config from serialized config is different"
+        );
+    }
+
+    #[test]
+    fn default_serde() {
+        let serialized_config =
+            serde_json::to_string(&ImageConfiguration::default()).expect("Failed to serialize");
+
+        assert_eq!(
+            ImageConfiguration::from_str(&serialized_config)
+                .expect("Could not deserialize from serialization"),
+            ImageConfiguration::default(),
+            "Deserialized default config is different"
+        );
+    }
+
+    #[test]
+    fn from_empty_str() {
+        let result = ImageConfiguration::from_str("");
+
+        assert!(
+            matches!(result, Err(ParsleyError::SerDe(ref err)) if err.is_eof()),
+            "Empty string was not rejected accordingly: {result:?}"
+        );
+    }
+
+    #[test]
+    fn from_oci() {
+        let oci_spec = config().oci_spec;
+        let image_config = ImageConfiguration::from_oci(oci_spec.clone());
+        let serialized = serde_json::to_value(&image_config).expect("Failed to serialize");
+
+        assert_eq!(
+            serialized,
+            serde_json::to_value(&oci_spec).expect("Failed to serialize"),
+            "Serialized configuration holds more than the OCI specification"
+        );
+        assert!(serialized["config"].get("Healthcheck").is_none());
+        assert!(serialized.get("docker_version").is_none());
+        assert_eq!(
+            serde_json::from_value::<ImageConfiguration>(serialized)
+                .expect("Failed to deserialize")
+                .oci_spec(),
+            &oci_spec
+        );
+    }
+
+    #[test]
+    fn display() {
+        let config = config();
+        let displayed = config.to_string();
+
+        assert!(displayed.contains('\n'), "Display is not pretty printed");
+        assert_eq!(
+            ImageConfiguration::from_str(&displayed).expect("Could not deserialize display"),
+            config,
+            "Displayed config does not match original one"
+        );
+    }
+
+    #[test]
+    fn labels() {
+        let config = config();
+
+        assert_eq!(config.labels(), BTreeMap::from([("maintainer", "someone")]));
+        assert_eq!(config.label("maintainer"), Some("someone"));
+        assert_eq!(config.label("missing"), None);
+        assert!(
+            ImageConfiguration::default().labels().is_empty(),
+            "Configuration without labels has labels"
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let old = config();
+        let mut new = old.clone();
+        let mut oci_config = new.oci_spec.config().clone().expect("Missing config");
+        let mut env = oci_config.env().clone().expect("Missing env");
+        let mut diff_ids = new.oci_spec.rootfs().diff_ids().clone();
+
+        env[0] = "PATH=/bin".to_owned();
+        oci_config.set_env(Some(env));
+        new.oci_spec.set_config(Some(oci_config));
+        diff_ids[2] = ABC_DIGEST.to_owned();
+        new.oci_spec.rootfs_mut().set_diff_ids(diff_ids);
+
+        assert_eq!(
+            old.diff(&new).expect("Could not diff"),
+            [
+                ConfigDifference {
+                    path: "config.Env[0]".to_owned(),
+                    old: old.oci_spec.config().as_ref().and_then(|config| {
+                        config.env().as_ref().map(|env| env[0].clone().into())
+                    }),
+                    new: Some("PATH=/bin".into()),
+                },
+                ConfigDifference {
+                    path: "rootfs.diff_ids[2]".to_owned(),
+                    old: Some(old.oci_spec.rootfs().diff_ids()[2].clone().into()),
+                    new: Some(ABC_DIGEST.into()),
+                },
+            ]
+        );
+        assert!(
+            old.diff(&old).expect("Could not diff").is_empty(),
+            "Identical configurations differ"
+        );
+    }
+
+    #[test]
+    fn for_each_field() {
+        let config = config();
+        let mut fields = BTreeMap::new();
+
+        config
+            .for_each_field(|path, value| {
+                fields.insert(path.to_owned(), value.clone());
+            })
+            .expect("Could not visit fields");
+
+        let json = serde_json::to_value(&config).expect("Could not serialize");
+
+        assert_eq!(fields.get("architecture"), Some(&"arm64".into()));
+        assert_eq!(fields.get("config.Env"), Some(&json["config"]["Env"]));
+        assert_eq!(
+            fields.get("rootfs.diff_ids"),
+            Some(&json["rootfs"]["diff_ids"])
+        );
+        assert!(fields.contains_key("config"), "Objects were not visited");
+        assert!(
+            fields.contains_key("config.Memory"),
+            "Docker fields were not visited"
+        );
+        assert!(
+            !fields.keys().any(|path| path.starts_with("config.Env[")),
+            "Array elements were visited"
+        );
+    }
+
+    #[test]
+    fn exposed_ports_parsed() {
+        assert_eq!(
+            config()
+                .exposed_ports_parsed()
+                .expect("Could not parse exposed ports"),
+            vec![(5432, Protocol::Tcp)]
+        );
+    }
+
+    #[test_case("5432/tcp" => Some((5432, Protocol::Tcp)); "Tcp")]
+    #[test_case("53/udp" => Some((53, Protocol::Udp)); "Udp")]
+    #[test_case("9899/SCTP" => Some((9899, Protocol::Sctp)); "Uppercase sctp")]
+    #[test_case("8080" => Some((8080, Protocol::Tcp)); "Default protocol")]
+    #[test_case("http/tcp" => None; "Named port")]
+    #[test_case("8000-8010/tcp" => None; "Port range")]
+    #[test_case("70000/tcp" => None; "Out of range port")]
+    #[test_case("80/icmp" => None; "Unknown protocol")]
+    #[test_case("" => None; "Empty")]
+    fn parse_exposed_port(port: &str) -> Option<(u16, Protocol)> {
+        super::parse_exposed_port(port).ok()
+    }
+
+    #[test]
+    fn platform() {
+        let platform = config().platform();
+
+        assert_eq!(platform.os(), &oci_spec::image::Os::Linux);
+        assert_eq!(platform.architecture(), &oci_spec::image::Arch::ARM64);
+        assert_eq!(platform.variant().as_deref(), Some("v8"));
+    }
+
+    #[test]
+    fn set_platform() {
+        let mut cross_config = config();
+
+        cross_config
+            .set_architecture(image::Arch::Amd64)
+            .set_os(image::Os::Windows)
+            .set_variant(None);
+
+        let serialized = serde_json::to_value(&cross_config).expect("Failed to serialize");
+
+        assert_eq!(serialized["architecture"], "amd64");
+        assert_eq!(serialized["os"], "windows");
+        assert!(serialized.get("variant").is_none());
+
+        let deserialized_config: ImageConfiguration =
+            serde_json::from_value(serialized).expect("Could not deserialize");
+
+        assert_eq!(deserialized_config, cross_config);
+        assert_eq!(
+            deserialized_config.docker_oci_extension(),
+            config().docker_oci_extension()
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn os_version_and_features() {
+        let windows_config =
+            ImageConfiguration::from_file(docker::tests::test_data_path("config_windows.json"))
+                .expect("Could not deserialize from file");
+
+        assert_eq!(windows_config.platform().os(), &image::Os::Windows);
+        assert_eq!(windows_config.os_version(), Some("10.0.20348.2113"));
+        assert_eq!(windows_config.os_features(), ["win32k"]);
+        assert!(config().os_version().is_none());
+        assert!(config().os_features().is_empty());
+    }
+
+    #[test_case(image::Arch::ARM64, Some("v8"), image::Arch::ARM64, Some("v8") => Ok(()); "Same variant")]
+    #[test_case(image::Arch::ARM64, None, image::Arch::ARM64, Some("v8") => Ok(()); "Image without variant")]
+    #[test_case(image::Arch::ARM64, Some("v8"), image::Arch::ARM64, None => Ok(()); "Host without variant")]
+    #[test_case(
+        image::Arch::ARM64, Some("v8"), image::Arch::ARM64, Some("v7")
+        => Err(PlatformMismatch::Variant { image: "v8".to_owned(), host: "v7".to_owned() });
+        "Other variant"
+    )]
+    #[test_case(
+        image::Arch::ARM64, Some("v8"), image::Arch::Amd64, None
+        => Err(PlatformMismatch::Architecture { image: image::Arch::ARM64, host: image::Arch::Amd64 });
+        "Other architecture"
+    )]
+    #[test_case(
+        image::Arch::Amd64, None, image::Arch::ARM64, Some("v8")
+        => Err(PlatformMismatch::Architecture { image: image::Arch::Amd64, host: image::Arch::ARM64 });
+        "Amd64 image on arm64 host"
+    )]
+    fn is_compatible_with(
+        architecture: image::Arch,
+        variant: Option<&str>,
+        host_architecture: image::Arch,
+        host_variant: Option<&str>,
+    ) -> Result<(), PlatformMismatch> {
+        let mut image_config = config();
+        let mut host = image::Platform::default();
+
+        image_config.oci_spec.set_architecture(architecture);
+        image_config
+            .oci_spec
+            .set_variant(variant.map(str::to_owned));
+        host.set_os(image::Os::Linux);
+        host.set_architecture(host_architecture);
+        host.set_variant(host_variant.map(str::to_owned));
+
+        image_config.is_compatible_with(&host)
+    }
+
+    #[test]
+    fn incompatible_os() {
+        let mut host = config().platform();
+
+        host.set_os(image::Os::Windows);
+
+        assert_eq!(
+            config().is_compatible_with(&host),
+            Err(PlatformMismatch::Os {
+                image: image::Os::Linux,
+                host: image::Os::Windows
+            })
+        );
+    }
+
+    #[test]
+    fn effective_env() {
+        let mut config = config();
+        let mut oci_config = config.oci_spec.config().clone().expect("Missing config");
+        let mut env = oci_config.env().clone().expect("Missing env");
+
+        env.extend(["PG_MAJOR=16".to_owned(), "EMPTY".to_owned()]);
+        oci_config.set_env(Some(env));
+        config.oci_spec.set_config(Some(oci_config));
+
+        assert_eq!(
+            config.effective_env(),
+            BTreeMap::from_iter([
+                ("EMPTY".to_owned(), "".to_owned()),
+                ("GOSU_VERSION".to_owned(), "1.16".to_owned()),
+                ("LANG".to_owned(), "en_US.utf8".to_owned()),
+                (
+                    "PATH".to_owned(),
+                    "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin:/usr/lib/postgresql/15/bin".to_owned()
+                ),
+                ("PGDATA".to_owned(), "/var/lib/postgresql/data".to_owned()),
+                ("PG_MAJOR".to_owned(), "16".to_owned()),
+                ("PG_VERSION".to_owned(), "15.4-1.pgdg120+1".to_owned()),
+            ]),
+            "Effective environment does not match expected one"
+        );
+    }
+
+    #[test]
+    fn healthcheck_null_duration() {
+        let health_check: HealthcheckConfig = serde_json::from_str(
+            "{\
+                \"Test\": [\"CMD-SHELL\", \"/usr/bin/check-health localhost\"],
+                \"Interval\": 30000000000,
+                \"Timeout\": null
+            }",
+        )
+        .expect("Could not deserialize healthcheck");
+
+        assert_eq!(
+            health_check,
+            HealthcheckConfigBuilder::default()
+                .test(vec![
+                    "CMD-SHELL".to_owned(),
+                    "/usr/bin/check-health localhost".to_owned(),
+                ])
+                .interval(Duration::from_nanos(30000000000))
+                .build()
+                .expect("Build healthcheck"),
+            "Null duration was not deserialized to None"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn seconds_duration_format() {
+        let config_path = docker::tests::test_data_path("config.json");
+        let mut json: serde_json::Value =
+            util::json::from_file(config_path).expect("Could not deserialize from file");
+        let health_check = &mut json["config"]["Healthcheck"];
+
+        health_check["Interval"] = 30.into();
+        health_check["Timeout"] = 10.into();
+        health_check["StartInterval"] = 3.into();
+
+        let deserialized_config = ImageConfiguration::from_str_with_duration_format(
+            &json.to_string(),
+            DurationFormat::Seconds,
+        )
+        .expect("Could not deserialize seconds durations");
+        let serialized_config = deserialized_config
+            .to_string_with_duration_format(DurationFormat::Seconds)
+            .expect("Failed to serialize");
+        let re_deserialized_config = ImageConfiguration::from_str_with_duration_format(
+            &serialized_config,
+            DurationFormat::Seconds,
+        )
+        .expect("Could not deserialize from serialization");
+
+        assert_eq!(
+            deserialized_config,
+            config(),
+            "Deserialized config does not match expected one"
+        );
+        assert_eq!(
+            re_deserialized_config, deserialized_config,
+            "Deserialized config from serialized config is different"
+        );
+    }
+
+    #[test]
+    fn seconds_duration_format_overflow() {
+        let s = r#"{"architecture":"amd64","os":"linux","config":{"Healthcheck":{"Test":["CMD","true"],"Interval":18446744073709551615}},"rootfs":{"type":"layers","diff_ids":[]}}"#;
+        let result = ImageConfiguration::from_str_with_duration_format(s, DurationFormat::Seconds);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::DurationOverflow(u64::MAX)
+                )))
+            ),
+            "Overflowing duration was not rejected: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn nanoseconds_duration_format() {
+        let config_path = docker::tests::test_data_path("config.json");
+        let s = std::fs::read_to_string(config_path).expect("Could not read file");
+        let deserialized_config =
+            ImageConfiguration::from_str_with_duration_format(&s, DurationFormat::Nanoseconds)
+                .expect("Could not deserialize nanoseconds durations");
+        let serialized_config = deserialized_config
+            .to_string_with_duration_format(DurationFormat::Nanoseconds)
+            .expect("Failed to serialize");
+
+        assert_eq!(
+            deserialized_config,
+            config(),
+            "Deserialized config does not match expected one"
+        );
+        assert_eq!(
+            ImageConfiguration::from_str(&serialized_config)
+                .expect("Could not deserialize from serialization"),
+            deserialized_config,
+            "Deserialized config from serialized config is different"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn mutate() {
+        let config_path = docker::tests::test_data_path("config.json");
+        let mut deserialized_config =
+            ImageConfiguration::from_file(config_path).expect("Could not deserialize from file");
+        let extension = ImageConfigurationExtensionBuilder::default()
+            .config(
+                ConfigExtensionBuilder::default()
+                    .memory(1024_u64)
+                    .build()
+                    .expect("Build Docker Config Extension"),
+            )
+            .build()
+            .expect("Docker OCI Image Extension");
+
+        deserialized_config.set_docker_oci_extension(Some(extension.clone()));
+        deserialized_config
+            .oci_spec_mut()
+            .set_author(Some("someone else".to_owned()));
+
+        let serialized_config =
+            serde_json::to_string(&deserialized_config).expect("Failed to serialize");
+        let re_deserialized_config = ImageConfiguration::from_str(&serialized_config)
+            .expect("Could not deserialize from serialization");
+
+        assert_eq!(
+            re_deserialized_config.docker_oci_extension(),
+            &Some(extension),
+            "Mutated extension did not survive serialization"
+        );
+        assert_eq!(
+            re_deserialized_config.oci_spec().author(),
+            &Some("someone else".to_owned()),
+            "Mutated OCI spec did not survive serialization"
+        );
+        assert_eq!(
+            re_deserialized_config.oci_spec().config(),
+            config().oci_spec().config(),
+            "OCI config was altered by the extension mutation"
+        );
+    }
+
+    #[test]
+    fn builder_extra_json() {
+        let image_config = ImageConfigurationBuilder::default()
+            .oci_spec(config().oci_spec().clone())
+            .docker_oci_extension(
+                config()
+                    .docker_oci_extension()
+                    .clone()
+                    .expect("Missing extension"),
+            )
+            .extra_json(serde_json::json!({"NewTopLevel": {"k": "v"}}))
+            .expect("Could not merge top level field")
+            .extra_json(serde_json::json!({"config": {"NewConfigField": true}}))
+            .expect("Could not merge config field")
+            .build()
+            .expect("Could not build configuration");
+        let json = serde_json::to_value(&image_config).expect("Failed to serialize");
+
+        assert_eq!(json["NewTopLevel"], serde_json::json!({"k": "v"}));
+        assert_eq!(json["config"]["NewConfigField"], true);
+        assert_eq!(json["config"]["Memory"], 2048, "Existing fields were lost");
+
+        let result = ImageConfigurationBuilder::default()
+            .extra_json(serde_json::json!({"config": {"Memory": "lots"}}));
+
+        assert!(
+            matches!(result, Err(ParsleyError::SerDe(_))),
+            "Invalid extension field was accepted"
+        );
+    }
+
+    #[test]
+    fn extension_value() {
+        let extension = config()
+            .docker_oci_extension()
+            .clone()
+            .expect("Missing extension");
+        let value = extension.to_value().expect("Could not convert to value");
+
+        assert_eq!(value["config"]["Memory"], 2048);
+        assert_eq!(value["docker_version"], "20.10.23");
+        assert_eq!(
+            ImageConfigurationExtension::from_value(value).expect("Could not convert from value"),
+            extension
+        );
+
+        let without_config = ImageConfigurationExtension {
+            config: None,
+            ..extension
+        };
+        let value = without_config
+            .to_value()
+            .expect("Could not convert to value");
+
+        assert!(
+            value.get("config").is_none(),
+            "Unset config was converted: {value}"
+        );
+    }
+
+    #[test]
+    fn merge_extension() {
+        let mut merged_config = config();
+        let extension = ImageConfigurationExtensionBuilder::default()
+            .config(
+                ConfigExtensionBuilder::default()
+                    .memory(4096_u64)
+                    .memory_swap(8192_u64)
                     .build()
                     .expect("Build Docker Config Extension"),
             )
             .build()
-            .expect("Docker OCI Image Extension");
-        let oci_spec = image::ImageConfigurationBuilder::default()
-            .created("2023-08-16T06:40:57.929475525Z")
-            .author("author".to_owned())
-            .architecture(image::Arch::ARM64)
-            .os(image::Os::Linux)
-            .config(image::ConfigBuilder::default()
-                .user("1001".to_owned())
-                .exposed_ports(vec!["5432/tcp".to_owned()])
-                .env(vec![
-                    "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin:/usr/lib/postgresql/15/bin".to_owned(),
-                    "GOSU_VERSION=1.16".to_owned(),
-                    "LANG=en_US.utf8".to_owned(),
-                    "PG_MAJOR=15".to_owned(),
-                    "PG_VERSION=15.4-1.pgdg120+1".to_owned(),
-                    "PGDATA=/var/lib/postgresql/data".to_owned()
-                ])
-                .entrypoint(vec![
-                    "docker-entrypoint.sh".to_owned()
-                ])
-                .labels(HashMap::from_iter([("maintainer".to_owned(),"someone".to_owned())]))
-                .stop_signal("SIGINT".to_owned())
-                .cmd(
-                    vec!["postgres".to_owned()]
-                )
-                .volumes(vec!["/var/lib/postgresql/data".to_owned()])
-                .working_dir("/postgres".to_owned())
-                .build()
-                .expect("Build Config")
-            )
-            .rootfs(image::RootFsBuilder::default()
-                .typ("layers")
-                .diff_ids(vec![
-                    "sha256:1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1".to_owned(),
-                    "sha256:310729fcb068da6941441d9627a3d8979e7dbd015c220324331e34af28b7e20c".to_owned(),
-                    "sha256:6cc6868915f4c4d399ec0026fd321acfd0b92e84cd2a51076e89041b3e3118b6".to_owned()
-                ]).build().expect("Rootfs"))
-            .history(vec![
-                image::HistoryBuilder::default()
-                    .created("2023-08-15T23:39:57.178505081Z".to_owned())
-                    .created_by("/bin/sh -c #(nop) ADD file:bc58956fa3d1aff2efb0264655d039fedfff28dc4ff19a65a235e82754ee1cfa in / ".to_owned())
-                    .build()
-                    .expect("Build History 1"),
-                image::HistoryBuilder::default()
-                    .created("2023-08-15T23:39:57.574431303Z".to_owned())
-                    .created_by("/bin/sh -c #(nop)  CMD [\"bash\"]".to_owned())
-                    .empty_layer(true)
-                    .build()
-                    .expect("Build History 2"),
-                image::HistoryBuilder::default()
-                    .created("2023-08-16T06:38:58.796057889Z".to_owned())
-                    .created_by("/bin/sh -c set -eux; \tgroupadd -r postgres --gid=999; \tuseradd -r -g postgres --uid=999 --home-dir=/var/lib/postgresql --shell=/bin/bash postgres; \tmkdir -p /var/lib/postgresql; \tchown -R postgres:postgres /var/lib/postgresql".to_owned())
-                    .build()
-                    .expect("Build History 3")
-            ])
-            .variant("v8".to_owned())
+            .expect("Docker OCI Image Extension");
+
+        merged_config
+            .merge_extension(extension)
+            .expect("Could not merge extension");
+
+        let json = serde_json::to_value(&merged_config).expect("Failed to serialize");
+        let re_deserialized_config =
+            ImageConfiguration::from_value(json.clone()).expect("Could not deserialize");
+        let config_extension = re_deserialized_config
+            .docker_oci_extension()
+            .as_ref()
+            .and_then(|extension| extension.config().as_ref())
+            .expect("Missing config extension");
+
+        assert_eq!(json["config"]["Memory"], 4096);
+        assert_eq!(json["config"]["MemorySwap"], 8192);
+        assert_eq!(config_extension.memory(), &Some(MemoryLimit::Bytes(4096)));
+        assert_eq!(
+            config_extension.on_build(),
+            config()
+                .docker_oci_extension()
+                .as_ref()
+                .and_then(|extension| extension.config().as_ref())
+                .expect("Missing config extension")
+                .on_build(),
+            "Unset extension fields were not kept"
+        );
+        assert_eq!(
+            re_deserialized_config.oci_spec(),
+            config().oci_spec(),
+            "OCI specification was altered by the merge"
+        );
+    }
+
+    #[test_case(Some(2048), Some(4096), Some(8) => true; "Consistent")]
+    #[test_case(Some(2048), None, None => true; "Memory only")]
+    #[test_case(None, Some(1024), None => true; "Memory swap only")]
+    #[test_case(Some(2048), Some(2048), None => true; "No swap")]
+    #[test_case(Some(2048), Some(1024), None => false; "Memory swap lower than memory")]
+    #[test_case(None, None, Some(0) => false; "No CPU shares")]
+    fn validate(memory: Option<u64>, memory_swap: Option<u64>, cpu_shares: Option<u16>) -> bool {
+        let config_extension = ConfigExtension {
+            memory: memory.map(MemoryLimit::from),
+            memory_swap: memory_swap.map(MemoryLimit::from),
+            cpu_shares,
+            ..ConfigExtension::default()
+        };
+
+        config_extension.validate().is_ok()
+    }
+
+    #[test]
+    fn validate_unlimited_swap() {
+        let config_extension = ConfigExtension {
+            memory: Some(MemoryLimit::Bytes(2048)),
+            memory_swap: Some(MemoryLimit::Unlimited),
+            ..ConfigExtension::default()
+        };
+
+        assert!(config_extension.validate().is_ok());
+    }
+
+    #[test_case(-1, MemoryLimit::Unlimited; "Unlimited")]
+    #[test_case(0, MemoryLimit::Bytes(0); "Zero")]
+    #[test_case(4096, MemoryLimit::Bytes(4096); "Bytes")]
+    fn memory_limit(json: i64, expected: MemoryLimit) {
+        let json = serde_json::json!({"Memory": json, "MemorySwap": json});
+        let config_extension: ConfigExtension =
+            serde_json::from_value(json.clone()).expect("Could not deserialize");
+
+        assert_eq!(config_extension.memory(), &Some(expected));
+        assert_eq!(config_extension.memory_swap(), &Some(expected));
+        assert_eq!(
+            serde_json::to_value(&config_extension).expect("Failed to serialize")["MemorySwap"],
+            json["MemorySwap"]
+        );
+    }
+
+    #[test]
+    fn invalid_memory_limit() {
+        let result = ConfigExtension::from_str(r#"{"MemorySwap": -2}"#);
+
+        assert!(
+            matches!(result, Err(ParsleyError::SerDe(_))),
+            "Invalid memory limit was accepted: {result:?}"
+        );
+    }
+
+    #[test_case(image::Os::Linux, None => vec!["/bin/sh", "-c"]; "Linux default")]
+    #[test_case(image::Os::Windows, None => vec!["cmd", "/S", "/C"]; "Windows default")]
+    #[test_case(
+        image::Os::Windows, Some(&["powershell", "-Command"]) => vec!["powershell", "-Command"];
+        "Explicit override"
+    )]
+    fn shell_or_default(os: image::Os, shell: Option<&[&str]>) -> Vec<String> {
+        let config_extension = ConfigExtension {
+            shell: shell.map(|shell| shell.iter().map(ToString::to_string).collect()),
+            ..ConfigExtension::default()
+        };
+
+        config_extension.shell_or_default(&os)
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn healthcheck_getters() {
+        let config = ImageConfiguration::from_file(docker::tests::test_data_path("config.json"))
+            .expect("Could not deserialize from file");
+        let health_check = config
+            .docker_oci_extension()
+            .as_ref()
+            .and_then(|extension| extension.config().as_ref())
+            .and_then(|config| config.health_check().as_ref())
+            .expect("Missing healthcheck");
+
+        assert_eq!(*health_check.interval(), Some(Duration::from_secs(30)));
+        assert_eq!(*health_check.timeout(), Some(Duration::from_secs(10)));
+        assert_eq!(*health_check.start_interval(), Some(Duration::from_secs(3)));
+        assert_eq!(*health_check.retries(), Some(3));
+        assert_eq!(
+            health_check.test().as_deref(),
+            Some(
+                [
+                    "CMD-SHELL".to_owned(),
+                    "/usr/bin/check-health localhost".to_owned()
+                ]
+                .as_slice()
+            )
+        );
+    }
+
+    #[test]
+    fn chain_ids() {
+        let mut image_config = config();
+
+        assert_eq!(
+            image_config.chain_ids(),
+            [
+                "sha256:1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1",
+                "sha256:c8f6bce3b4ae6a7983ce5b6fbf45747f1deb7b790d4624f2a7f695e9cdae12c7",
+                "sha256:4b78b70e7db42924d10e2b0b921b9c9b1ab686ab7a6210f088aff922d3fdef21",
+            ]
+        );
+
+        image_config.oci_spec.rootfs_mut().set_diff_ids(Vec::new());
+
+        assert!(image_config.chain_ids().is_empty());
+    }
+
+    #[test_case(3 => true; "Matching")]
+    #[test_case(2 => false; "Fewer layers")]
+    #[test_case(4 => false; "More layers")]
+    fn assert_layer_count(layer_count: usize) -> bool {
+        let item = docker::image::ManifestItemBuilder::default()
+            .layers(vec!["layer.tar".to_owned(); layer_count])
+            .build()
+            .expect("Could not build manifest item");
+        let result = config().assert_layer_count(&item);
+
+        if let Err(ref err) = result {
+            assert!(
+                matches!(
+                    err,
+                    ParsleyError::Docker(docker::Error::ImageError(Error::LayerCountMismatch {
+                        expected: 3,
+                        actual
+                    })) if *actual == layer_count
+                ),
+                "Unexpected error: {err:?}"
+            );
+        }
+
+        result.is_ok()
+    }
+
+    #[test_case("Healthcheck"; "Docker key")]
+    #[test_case("HealthCheck"; "PascalCase key")]
+    fn healthcheck_key(key: &str) {
+        let s = format!(r#"{{"Env": [], "{key}": {{"Test": ["CMD", "true"]}}}}"#);
+        let extension = ConfigExtension::from_str(&s).expect("Could not deserialize");
+        let serialized = serde_json::to_value(&extension).expect("Failed to serialize");
+
+        assert!(
+            extension.health_check().is_some(),
+            "Healthcheck was dropped"
+        );
+        assert_eq!(
+            serialized["Healthcheck"]["Test"],
+            serde_json::json!(["CMD", "true"]),
+            "Healthcheck is not serialized under the key Docker writes"
+        );
+        assert!(serialized.get("HealthCheck").is_none());
+    }
+
+    #[test]
+    fn healthcheck_yaml() {
+        let health_check = HealthcheckConfigBuilder::default()
+            .test(vec!["CMD".to_owned(), "pg_isready".to_owned()])
+            .interval(Duration::from_secs(30))
+            .start_interval(Duration::from_nanos(1))
             .build()
-            .expect("OCI Config Spec");
+            .expect("Could not build healthcheck");
+        let serialized = serde_yaml::to_string(&health_check).expect("Failed to serialize");
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&serialized).expect("Invalid YAML");
 
-        ImageConfigurationBuilder::default()
-            .docker_oci_extension(docker_oci_extension)
-            .oci_spec(oci_spec)
+        assert_eq!(yaml["Interval"], serde_yaml::Value::from(30000000000_u64));
+        assert_eq!(yaml["StartInterval"], serde_yaml::Value::from(1_u64));
+        assert_eq!(
+            serde_yaml::from_str::<HealthcheckConfig>(&serialized).expect("Failed to deserialize"),
+            health_check,
+            "Healthcheck does not round trip through YAML"
+        );
+        assert_eq!(
+            serde_yaml::from_str::<HealthcheckConfig>("Test: [CMD]\nInterval: 1m30s\nTimeout: ~")
+                .expect("Failed to deserialize")
+                .interval(),
+            &Some(Duration::from_secs(90))
+        );
+    }
+
+    #[test]
+    fn healthcheck_builder() {
+        HealthcheckConfigBuilder::default()
+            .test(vec![
+                "CMD-SHELL".to_owned(),
+                "/usr/bin/check-health localhost".to_owned(),
+            ])
+            .interval(Duration::from_secs(30))
+            .retries(3_u32)
             .build()
-            .expect("Image Config")
+            .expect("Valid healthcheck rejected");
+        HealthcheckConfigBuilder::default()
+            .build()
+            .expect("Empty healthcheck rejected");
+    }
+
+    #[test_case(HealthcheckConfigBuilder::default().retries(3_u32); "Retries without test")]
+    #[test_case(
+        HealthcheckConfigBuilder::default().test(Vec::default()).interval(Duration::from_secs(30));
+        "Interval with empty test"
+    )]
+    fn invalid_healthcheck_builder(builder: HealthcheckConfigBuilder) {
+        let result = builder.build();
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::InvalidHealthcheckConfiguration
+                )))
+            ),
+            "Invalid healthcheck was not rejected: {result:?}"
+        );
     }
 
     #[test]
-    fn deserialize() {
+    fn builder_missing_oci_spec() {
+        let result = ImageConfigurationBuilder::default()
+            .docker_oci_extension(ImageConfigurationExtension::default())
+            .build();
+
+        match result {
+            Err(ParsleyError::Docker(docker::Error::ImageError(err))) => {
+                assert_eq!(err.to_string(), "ImageConfiguration requires oci_spec")
+            }
+            result => panic!("Missing OCI specifications were not rejected: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn to_canonical_vec() {
+        let config = config();
+        let first = config.to_canonical_vec().expect("Could not serialize");
+        let second = config.to_canonical_vec().expect("Could not serialize");
+
+        assert_eq!(first, second, "Canonical serialization is not stable");
+        assert_eq!(
+            ImageConfiguration::from_slice(&first)
+                .expect("Could not deserialize")
+                .to_canonical_vec()
+                .expect("Could not serialize"),
+            first,
+            "Canonical serialization does not round trip"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn digest() {
         let config_path = docker::tests::test_data_path("config.json");
         let deserialized_config =
             ImageConfiguration::from_file(config_path).expect("Could not deserialize from file");
+        let output_dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let output_path = output_dir.path().join("config.json");
+
+        deserialized_config
+            .to_file(&output_path)
+            .expect("Could not serialize to file");
+
+        let digest = deserialized_config
+            .digest()
+            .expect("Could not compute digest");
+
+        assert_eq!(
+            digest,
+            ImageConfiguration::digest_of_file(&output_path).expect("Could not compute digest"),
+            "Digest differs from the written file digest"
+        );
+        assert_eq!(
+            digest, FIXTURE_DIGEST,
+            "Digest differs from the known fixture digest"
+        );
+        assert_eq!(
+            ImageConfiguration::from_file(&output_path)
+                .expect("Could not deserialize from written file")
+                .digest()
+                .expect("Could not compute digest"),
+            digest,
+            "Digest is not stable across round trips"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn digest_of_blob() {
+        let config_path =
+            docker::tests::test_data_path(docker::tests::CONTENT_ADDRESSED_CONFIG_PATH);
+        let expected_digest = format!(
+            "sha256:{}",
+            docker::tests::CONTENT_ADDRESSED_CONFIG_PATH.trim_end_matches(".json")
+        );
+
+        assert_eq!(
+            ImageConfiguration::digest_of_file(&config_path).expect("Could not compute digest"),
+            expected_digest,
+            "Digest differs from the one naming the fixture"
+        );
+        assert_eq!(
+            ImageConfiguration::digest_of_slice(
+                &std::fs::read(&config_path).expect("Could not read file")
+            ),
+            expected_digest,
+            "Digest differs from the one naming the fixture"
+        );
+    }
+
+    #[test]
+    fn created_at() {
+        let created_at = config()
+            .created_at()
+            .expect("Could not parse created timestamp")
+            .expect("Missing created timestamp");
+
+        assert_eq!(
+            created_at,
+            time::macros::datetime!(2023-08-16 06:40:57.929475525 UTC)
+        );
+        assert_eq!(created_at.nanosecond(), 929475525);
+    }
+
+    #[test]
+    fn invalid_created_at() {
+        let mut image_config = config();
+
+        image_config
+            .oci_spec
+            .set_created(Some("yesterday".to_owned()));
+
+        assert!(
+            matches!(
+                image_config.created_at(),
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::InvalidCreatedTimestamp(ref created)
+                ))) if created == "yesterday"
+            ),
+            "Invalid created timestamp was not rejected"
+        );
+
+        image_config.oci_spec.set_created(None);
+
+        assert!(image_config
+            .created_at()
+            .expect("Missing timestamp was rejected")
+            .is_none());
+    }
+
+    #[test]
+    fn eq_ignoring_timestamps() {
+        let image_config = config();
+        let mut rebuilt_config = image_config.clone();
+
+        rebuilt_config
+            .oci_spec
+            .set_created(Some("2024-01-01T00:00:00Z".to_owned()));
+
+        for entry in rebuilt_config.oci_spec.history_mut() {
+            entry.set_created(Some("2024-01-01T00:00:00Z".to_owned()));
+        }
+
+        assert_ne!(image_config, rebuilt_config);
+        assert!(
+            image_config.eq_ignoring_timestamps(&rebuilt_config),
+            "Configurations differing in timestamps only are not equal"
+        );
+
+        rebuilt_config
+            .oci_spec
+            .set_author(Some("someone else".to_owned()));
+
+        assert!(
+            !image_config.eq_ignoring_timestamps(&rebuilt_config),
+            "Configurations differing in author are equal"
+        );
+    }
+
+    #[test]
+    fn history_created_at() {
+        let image_config = config();
+        let created_at = image_config
+            .history_created_at()
+            .expect("Could not parse history timestamps");
+
+        assert_eq!(created_at.len(), image_config.oci_spec.history().len());
+        assert_eq!(
+            created_at[0],
+            Some(time::macros::datetime!(2023-08-15 23:39:57.178505081 UTC))
+        );
+    }
+
+    fn command_config(
+        os: image::Os,
+        entrypoint: Option<&[&str]>,
+        cmd: Option<&[&str]>,
+        args_escaped: bool,
+    ) -> ImageConfiguration {
+        let mut image_config = config();
+        let mut oci_config = image_config.oci_spec.config().clone().unwrap_or_default();
+        let to_vec = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        oci_config.set_entrypoint(entrypoint.map(to_vec));
+        oci_config.set_cmd(cmd.map(to_vec));
+        image_config.oci_spec.set_os(os);
+        image_config.oci_spec.set_config(Some(oci_config));
+        image_config
+            .docker_oci_extension
+            .as_mut()
+            .and_then(|extension| extension.config.as_mut())
+            .expect("Missing config extension")
+            .args_escaped = args_escaped;
+
+        image_config
+    }
+
+    #[test_case(
+        image::Os::Linux, Some(&["docker-entrypoint.sh"]), Some(&["postgres"]), false
+        => CommandLine::Args(vec!["docker-entrypoint.sh".to_owned(), "postgres".to_owned()]);
+        "Linux entrypoint and cmd"
+    )]
+    #[test_case(
+        image::Os::Linux, None, Some(&["postgres", "-c", "fsync=off"]), true
+        => CommandLine::Args(vec!["postgres".to_owned(), "-c".to_owned(), "fsync=off".to_owned()]);
+        "Linux ignores args escaped"
+    )]
+    #[test_case(
+        image::Os::Windows, None, Some(&["cmd /S /C \"echo hello\""]), true
+        => CommandLine::Escaped("cmd /S /C \"echo hello\"".to_owned());
+        "Windows escaped cmd"
+    )]
+    #[test_case(
+        image::Os::Windows, Some(&["powershell -Command"]), Some(&["Get-Item", "C:\\Program Files", "a\"b"]), true
+        => CommandLine::Escaped(
+            "powershell -Command Get-Item \"C:\\Program Files\" a\\\"b".to_owned()
+        );
+        "Windows escaped entrypoint with arguments"
+    )]
+    #[test_case(
+        image::Os::Windows, Some(&["cmd", "/C"]), Some(&["dir"]), false
+        => CommandLine::Args(vec!["cmd".to_owned(), "/C".to_owned(), "dir".to_owned()]);
+        "Windows without args escaped"
+    )]
+    #[test_case(image::Os::Linux, None, None, false => CommandLine::Args(Vec::new()); "No command")]
+    fn entrypoint_and_cmd(
+        os: image::Os,
+        entrypoint: Option<&[&str]>,
+        cmd: Option<&[&str]>,
+        args_escaped: bool,
+    ) -> CommandLine {
+        command_config(os, entrypoint, cmd, args_escaped).entrypoint_and_cmd()
+    }
+
+    #[test_case("" => "\"\""; "Empty")]
+    #[test_case("plain" => "plain"; "Plain")]
+    #[test_case("with space" => "\"with space\""; "Space")]
+    #[test_case("C:\\dir\\" => "C:\\dir\\"; "Trailing backslash without space")]
+    #[test_case("C:\\my dir\\" => "\"C:\\my dir\\\\\""; "Trailing backslash with space")]
+    #[test_case("say \"hi\"" => "\"say \\\"hi\\\"\""; "Quotes")]
+    fn escape_windows_arg(arg: &str) -> String {
+        super::escape_windows_arg(arg)
+    }
+
+    #[test]
+    fn history_sorted_by_created() {
+        let expected = config();
+        let mut config = expected.clone();
+        let mut history = config.oci_spec.history().clone();
+
+        history.reverse();
+        config.oci_spec.set_history(history);
+
+        assert_eq!(
+            config
+                .history_sorted_by_created()
+                .expect("Could not sort history"),
+            expected.oci_spec.history().iter().collect::<Vec<_>>(),
+            "History is not in chronological order"
+        );
+    }
+
+    #[test]
+    fn history_sorted_by_created_malformed() {
+        let mut config = config();
+        let mut history = config.oci_spec.history().clone();
+
+        history[1].set_created(Some("2023-08-15 23:39:57".to_owned()));
+        config.oci_spec.set_history(history);
+
+        let result = config.history_sorted_by_created();
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::InvalidHistoryTimestamp { index: 1, .. }
+                )))
+            ),
+            "Malformed timestamp was not reported: {result:?}"
+        );
+    }
+
+    /// Digest of the config fixture, key sorted and compacted
+    #[cfg(feature = "fs")]
+    const FIXTURE_DIGEST: &str =
+        "sha256:7a497c1b83561ecf5149f251914c3530b69d4eac92f831cded919933e352d328";
+
+    /// Digest of the empty content
+    const EMPTY_DIGEST: &str =
+        "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    /// Digest of the `abc` content
+    const ABC_DIGEST: &str =
+        "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn config_with_diff_ids(diff_ids: &[&str]) -> ImageConfiguration {
+        let mut config = config();
+
+        config.oci_spec.set_rootfs(
+            image::RootFsBuilder::default()
+                .typ("layers")
+                .diff_ids(diff_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>())
+                .build()
+                .expect("Rootfs"),
+        );
+
+        config
+    }
+
+    #[test]
+    fn push_history_layer() {
+        let mut config = ImageConfiguration::default();
+
+        config
+            .push_history_layer(EMPTY_DIGEST, "/bin/sh -c #(nop) ADD file:a in / ", false)
+            .expect("Could not push layer");
+        config
+            .push_history_layer("", "/bin/sh -c #(nop)  CMD [\"bash\"]", true)
+            .expect("Could not push empty layer");
+        config
+            .push_history_layer(ABC_DIGEST, "/bin/sh -c apt-get update", false)
+            .expect("Could not push layer");
+
+        let history = config.oci_spec().history();
+
+        assert_eq!(
+            *config.oci_spec().rootfs().diff_ids(),
+            [EMPTY_DIGEST, ABC_DIGEST],
+            "Unexpected diff ids"
+        );
+        assert_eq!(history.len(), 3, "Unexpected number of history entries");
+        assert_eq!(
+            history
+                .iter()
+                .filter(|entry| !entry.empty_layer().unwrap_or_default())
+                .count(),
+            config.oci_spec().rootfs().diff_ids().len(),
+            "Non empty history entries differ from diff ids"
+        );
+        assert!(
+            config.push_history_layer("sha256:abc", "", false).is_err(),
+            "Invalid diff id was accepted"
+        );
+        assert_eq!(
+            config.oci_spec().history().len(),
+            3,
+            "Rejected layer was recorded"
+        );
+    }
+
+    #[test]
+    fn verify_diff_ids() {
+        let config = config_with_diff_ids(&[EMPTY_DIGEST, ABC_DIGEST]);
+        let layers: [&[u8]; 2] = [b"", b"abc"];
+
+        config
+            .verify_diff_ids(layers.into_iter())
+            .expect("Diff ids do not match layers");
+    }
+
+    #[test]
+    fn verify_diff_ids_mismatch() {
+        let config = config_with_diff_ids(&[EMPTY_DIGEST, EMPTY_DIGEST]);
+        let layers: [&[u8]; 2] = [b"", b"abc"];
+        let result = config.verify_diff_ids(layers.into_iter());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::DiffIdMismatch { index: 1, ref expected, ref actual }
+                ))) if expected == EMPTY_DIGEST && actual == ABC_DIGEST
+            ),
+            "Mismatching layer was not reported: {result:?}"
+        );
+    }
+
+    #[test]
+    fn verify_diff_ids_count_mismatch() {
+        let config = config_with_diff_ids(&[EMPTY_DIGEST, ABC_DIGEST]);
+        let missing_layers: [&[u8]; 1] = [b""];
+        let extra_layers: [&[u8]; 3] = [b"", b"abc", b"abc"];
+
+        for (layers, actual_count) in [(&missing_layers[..], 1), (&extra_layers[..], 3)] {
+            let result = config.verify_diff_ids(layers.iter().copied());
+
+            assert!(
+                matches!(
+                    result,
+                    Err(ParsleyError::Docker(docker::Error::ImageError(
+                        Error::LayerCountMismatch {
+                            expected: 2,
+                            actual
+                        }
+                    ))) if actual == actual_count
+                ),
+                "Layer count mismatch was not reported: {result:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn from_file_async() {
+        let config_path = docker::tests::test_data_path("config.json");
+        let deserialized_config = ImageConfiguration::from_file_async(&config_path)
+            .await
+            .expect("Could not deserialize from file");
 
         assert_eq!(
             deserialized_config,
             config(),
             "Deserialized config does not match expected one"
         );
+
+        let result =
+            ImageConfiguration::from_file_async(config_path.with_extension("missing")).await;
+
+        assert!(
+            matches!(result, Err(ParsleyError::Io(_))),
+            "Missing file did not fail with an IO error: {result:?}"
+        );
     }
 
+    #[cfg(feature = "fs")]
     #[test]
-    fn serde() {
+    fn to_file() {
         let config_path = docker::tests::test_data_path("config.json");
-        let deserialized_config = ImageConfiguration::from_file(config_path)
-            .expect("Could not deserialize from file {config_path}");
-        let serialized_config =
-            serde_json::to_string(&deserialized_config).expect("Failed to serialize");
-        let re_deserialized_config = ImageConfiguration::from_str(&serialized_config)
-            .expect("Could not deserialize from serialization");
+        let deserialized_config =
+            ImageConfiguration::from_file(config_path).expect("Could not deserialize from file");
+        let output_dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let output_path = output_dir.path().join("config.json");
+
+        deserialized_config
+            .to_file(&output_path)
+            .expect("Could not serialize to file");
+        let re_deserialized_config = ImageConfiguration::from_file(&output_path)
+            .expect("Could not deserialize from written file");
 
         assert_eq!(
             deserialized_config, re_deserialized_config,
-            "Deserialized config from serialized config is different"
+            "Deserialized config from written file is different"
         );
     }
 }
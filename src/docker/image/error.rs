@@ -12,10 +12,170 @@ pub enum Error {
     InvalidImageManifest,
 
     /// Error caused by missing configuration file
-    #[error("manifest is missing from docker image")]
+    #[error("configuration is missing from docker image")]
     MissingImageConfiguration,
 
     /// Error caused by invalid content of configuration file
-    #[error("invalid content in manifest file")]
+    #[error("invalid content in configuration file")]
     InvalidImageConfiguration,
+
+    /// Error caused by a layer referenced by the manifest that is missing
+    #[error("layer {0} is missing from docker image")]
+    MissingLayer(String),
+
+    /// Error caused by a layer referenced by the manifest that is not a regular file
+    #[error("invalid content in layer {0}")]
+    InvalidLayer(String),
+
+    /// Error caused by a layer whose digest differs from the one recorded in the configuration
+    #[error("diff id mismatch for layer {index}: expected {expected}, got {actual}")]
+    DiffIdMismatch {
+        index: usize,
+        expected: String,
+        actual: String,
+    },
+
+    /// Error caused by a configuration whose digest differs from the one its manifest path names
+    #[error("configuration digest mismatch: expected {expected}, got {actual}")]
+    ConfigDigestMismatch { expected: String, actual: String },
+
+    /// Error caused by a number of layers different from the one recorded in the configuration
+    #[error("layer count mismatch: expected {expected}, got {actual}")]
+    LayerCountMismatch { expected: usize, actual: usize },
+
+    /// Error caused by a number of configurations different from the number of manifest items
+    #[error("configuration count mismatch: expected {expected}, got {actual}")]
+    ConfigurationCountMismatch { expected: usize, actual: usize },
+
+    /// Error caused by a total memory limit (memory + swap) lower than the memory limit
+    #[error(
+        "invalid memory configuration: memory swap {memory_swap} is lower than memory {memory}"
+    )]
+    InvalidMemoryConfiguration { memory: u64, memory_swap: u64 },
+
+    /// Error caused by CPU shares set to 0
+    #[error("invalid cpu configuration: cpu shares must be greater than 0")]
+    InvalidCpuConfiguration,
+
+    /// Error caused by a healthcheck that sets its parameters without a test to run
+    #[error("invalid healthcheck configuration: a test is required when interval, timeout, start interval or retries are set")]
+    InvalidHealthcheckConfiguration,
+
+    /// Error caused by a healthcheck duration in seconds that overflows a count of nanoseconds
+    #[error("healthcheck duration of {0} seconds overflows nanoseconds")]
+    DurationOverflow(u64),
+
+    /// Error caused by content expected to be gzip compressed that is not
+    #[error("content is not gzip compressed")]
+    NotGzipCompressed,
+
+    /// Error caused by a configuration whose creation time is not an RFC 3339 timestamp
+    #[error("invalid created timestamp {0:?} of configuration")]
+    InvalidCreatedTimestamp(String),
+
+    /// Error caused by a history entry whose creation time is not an RFC 3339 timestamp
+    #[error("invalid created timestamp {created:?} of history entry {index}")]
+    InvalidHistoryTimestamp { index: usize, created: String },
+
+    /// Error caused by an exposed port that is not shaped as `port[/protocol]`
+    #[error("invalid exposed port {0:?}")]
+    InvalidExposedPort(String),
+
+    /// Error caused by a builder missing a field that its structure cannot do without
+    #[error("{structure} requires {field}")]
+    MissingRequiredField { structure: String, field: String },
+
+    /// Error caused by a field that is unknown to the Docker image format, when parsing strictly
+    #[error("unknown field {0} in configuration file")]
+    UnknownField(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Error::MissingImageManifest, "manifest is missing from docker image"; "Missing manifest")]
+    #[test_case(Error::InvalidImageManifest, "invalid content in manifest file"; "Invalid manifest")]
+    #[test_case(Error::MissingImageConfiguration, "configuration is missing from docker image"; "Missing configuration")]
+    #[test_case(Error::InvalidImageConfiguration, "invalid content in configuration file"; "Invalid configuration")]
+    #[test_case(
+        Error::MissingLayer("3b05311756d9/layer.tar".to_owned()),
+        "layer 3b05311756d9/layer.tar is missing from docker image";
+        "Missing layer"
+    )]
+    #[test_case(
+        Error::InvalidLayer("3b05311756d9/layer.tar".to_owned()),
+        "invalid content in layer 3b05311756d9/layer.tar";
+        "Invalid layer"
+    )]
+    #[test_case(
+        Error::DiffIdMismatch { index: 1, expected: "sha256:a".to_owned(), actual: "sha256:b".to_owned() },
+        "diff id mismatch for layer 1: expected sha256:a, got sha256:b";
+        "Diff id mismatch"
+    )]
+    #[test_case(
+        Error::ConfigDigestMismatch { expected: "sha256:a".to_owned(), actual: "sha256:b".to_owned() },
+        "configuration digest mismatch: expected sha256:a, got sha256:b";
+        "Configuration digest mismatch"
+    )]
+    #[test_case(
+        Error::LayerCountMismatch { expected: 3, actual: 2 },
+        "layer count mismatch: expected 3, got 2";
+        "Layer count mismatch"
+    )]
+    #[test_case(
+        Error::ConfigurationCountMismatch { expected: 2, actual: 1 },
+        "configuration count mismatch: expected 2, got 1";
+        "Configuration count mismatch"
+    )]
+    #[test_case(
+        Error::InvalidMemoryConfiguration { memory: 2048, memory_swap: 1024 },
+        "invalid memory configuration: memory swap 1024 is lower than memory 2048";
+        "Invalid memory configuration"
+    )]
+    #[test_case(
+        Error::InvalidCpuConfiguration,
+        "invalid cpu configuration: cpu shares must be greater than 0";
+        "Invalid cpu configuration"
+    )]
+    #[test_case(
+        Error::InvalidHealthcheckConfiguration,
+        "invalid healthcheck configuration: a test is required when interval, timeout, start interval or retries are set";
+        "Invalid healthcheck configuration"
+    )]
+    #[test_case(
+        Error::DurationOverflow(u64::MAX),
+        "healthcheck duration of 18446744073709551615 seconds overflows nanoseconds";
+        "Duration overflow"
+    )]
+    #[test_case(Error::NotGzipCompressed, "content is not gzip compressed"; "Not gzip compressed")]
+    #[test_case(
+        Error::InvalidCreatedTimestamp("yesterday".to_owned()),
+        "invalid created timestamp \"yesterday\" of configuration";
+        "Invalid created timestamp"
+    )]
+    #[test_case(
+        Error::InvalidHistoryTimestamp { index: 2, created: "yesterday".to_owned() },
+        "invalid created timestamp \"yesterday\" of history entry 2";
+        "Invalid history timestamp"
+    )]
+    #[test_case(
+        Error::InvalidExposedPort("http/tcp".to_owned()),
+        "invalid exposed port \"http/tcp\"";
+        "Invalid exposed port"
+    )]
+    #[test_case(
+        Error::MissingRequiredField { structure: "ImageConfiguration".to_owned(), field: "oci_spec".to_owned() },
+        "ImageConfiguration requires oci_spec";
+        "Missing required field"
+    )]
+    #[test_case(
+        Error::UnknownField("config.Memmory".to_owned()),
+        "unknown field config.Memmory in configuration file";
+        "Unknown field"
+    )]
+    fn display(error: Error, expected: &str) {
+        assert_eq!(error.to_string(), expected);
+    }
 }
@@ -1,10 +1,17 @@
+use crate::docker;
+use crate::docker::image::error::Error;
+use crate::docker::image::ImageConfiguration;
 use crate::error::{ParsleyError, ParsleyResult};
 use crate::util;
 use derive_builder::Builder;
 use getset::Getters;
 use oci_spec;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+#[cfg(feature = "fs")]
+use std::io;
+use std::io::{Read, Write};
+#[cfg(feature = "fs")]
 use std::path::Path;
 use std::str::FromStr;
 
@@ -44,6 +51,274 @@ pub struct ManifestItem {
     layer_sources: Option<BTreeMap<String, oci_spec::image::Descriptor>>,
 }
 
+/// A layer of an image, tying together what the manifest item and the configuration record about
+/// it, as assembled by [ManifestItem::layers_resolved](ManifestItem::layers_resolved).
+#[derive(Getters, Clone, Debug, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct Layer {
+    /// Path of the layer within the artifact (e.g. `<id>/layer.tar`).
+    path: String,
+    /// Digest of the uncompressed layer, as recorded in the `rootfs` of the configuration.
+    diff_id: String,
+    /// Descriptor of the layer recorded in `LayerSources`, for foreign layers only.
+    descriptor: Option<oci_spec::image::Descriptor>,
+}
+
+/// The cheap fields of a [ManifestItem](ManifestItem), as parsed by
+/// [ImageManifest::summaries_from_reader](ImageManifest::summaries_from_reader), e.g. for listing
+/// the images of a large `docker save` artifact.
+///
+/// The layers and their `LayerSources` descriptors are skipped without being deserialized.
+#[derive(Getters, Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+#[getset(get = "pub")]
+pub struct ManifestSummary {
+    /// Path of the configuration within the artifact.
+    config: String,
+    /// Tags of the image (e.g. `postgres:15.4`).
+    #[serde(default)]
+    repo_tags: Vec<String>,
+    /// ID of the parent image, if any.
+    #[serde(default)]
+    parent: Option<String>,
+}
+
+/// Suffix of the layer paths of a `docker save` archive, following the layer directory.
+const LAYER_FILE_SUFFIX: &str = "/layer.tar";
+
+impl ManifestItem {
+    /// Number of layers of the image.
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Iterates over the layer directory IDs, in order, by stripping the `/layer.tar` suffix of
+    /// the layer paths. Paths without the suffix are yielded as is.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image::ManifestItemBuilder;
+    ///
+    /// let item = ManifestItemBuilder::default()
+    ///     .layers(vec!["3b05311756d9/layer.tar".to_owned()])
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(item.layer_dirs().collect::<Vec<_>>(), ["3b05311756d9"]);
+    /// ```
+    pub fn layer_dirs(&self) -> impl Iterator<Item = &str> {
+        self.layers
+            .iter()
+            .map(|layer| layer.strip_suffix(LAYER_FILE_SUFFIX).unwrap_or(layer))
+    }
+
+    /// Looks up the descriptor of a foreign layer (e.g. a Windows base layer) by the key it is
+    /// recorded under in `LayerSources`, which `docker save` sets to the diff id of the layer.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+    /// let image_config = image_manifest.0[0].load_configuration(".").unwrap();
+    ///
+    /// for diff_id in image_config.oci_spec().rootfs().diff_ids() {
+    ///     if let Some(descriptor) = image_manifest.0[0].layer_source(diff_id) {
+    ///         println!("{diff_id} is fetched from {:?}", descriptor.urls());
+    ///     }
+    /// }
+    /// ```
+    pub fn layer_source(&self, layer: &str) -> Option<&oci_spec::image::Descriptor> {
+        self.layer_sources.as_ref()?.get(layer)
+    }
+
+    /// Looks up the annotations (e.g. `org.opencontainers.image.title`) of the descriptor of a
+    /// foreign layer, by the diff id it is recorded under in `LayerSources`, like
+    /// [layer_source](ManifestItem::layer_source). `None` if the layer has no descriptor or the
+    /// descriptor has no annotations.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+    ///
+    /// for (diff_id, _) in image_manifest.0[0].layer_sources_iter() {
+    ///     if let Some(annotations) = image_manifest.0[0].layer_annotations(diff_id) {
+    ///         println!("{diff_id}: {:?}", annotations.get("org.opencontainers.image.title"));
+    ///     }
+    /// }
+    /// ```
+    pub fn layer_annotations(&self, layer: &str) -> Option<&HashMap<String, String>> {
+        self.layer_source(layer)?.annotations().as_ref()
+    }
+
+    /// Iterates over the foreign layers and their descriptors, ordered by key. Yields nothing if
+    /// the item has no `LayerSources`.
+    pub fn layer_sources_iter(&self) -> impl Iterator<Item = (&str, &oci_spec::image::Descriptor)> {
+        self.layer_sources
+            .iter()
+            .flatten()
+            .map(|(layer, descriptor)| (layer.as_str(), descriptor))
+    }
+
+    /// Digest of the configuration, as named by its path: `<hex>.json` for the legacy
+    /// `docker save` layout and `blobs/<algorithm>/<hex>` for the OCI one. `None` if the path
+    /// does not name a valid digest.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image::ManifestItemBuilder;
+    ///
+    /// let hex = "ee56d70bcdf1aeca472a9899de653eb4d72f4a3ac31d9b0b95e677488ce766f3";
+    /// let item = ManifestItemBuilder::default()
+    ///     .config(format!("{hex}.json"))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(item.expected_config_digest(), Some(format!("sha256:{hex}")));
+    /// ```
+    pub fn expected_config_digest(&self) -> Option<String> {
+        let digest = match self.config.strip_suffix(".json") {
+            Some(hex) => format!("sha256:{hex}"),
+            None => {
+                let (algorithm, hex) = self.config.strip_prefix("blobs/")?.split_once('/')?;
+
+                format!("{algorithm}:{hex}")
+            }
+        };
+
+        docker::digest::Digest::from_str(&digest)
+            .ok()
+            .map(|digest| digest.to_string())
+    }
+
+    /// Checks that the digest of `config`, the raw bytes of the configuration blob, is the one
+    /// named by the configuration path of the item.
+    ///
+    /// The bytes must be those of the blob as stored, since a configuration that went through
+    /// deserialization and serialization is not guaranteed to hash to the same digest.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the configuration path does not name a
+    /// digest or the digests differ.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("postgres/manifest.json").unwrap();
+    /// let item = &image_manifest.0[0];
+    /// let config = std::fs::read(format!("postgres/{}", item.config())).unwrap();
+    ///
+    /// item.verify_config(&config).unwrap();
+    /// ```
+    pub fn verify_config(&self, config: &[u8]) -> ParsleyResult<()> {
+        let expected = self
+            .expected_config_digest()
+            .ok_or(docker::Error::from(Error::InvalidImageManifest))?;
+        let actual = docker::digest::Digest::sha256(config).to_string();
+
+        if expected != actual {
+            return Err(
+                docker::Error::from(Error::ConfigDigestMismatch { expected, actual }).into(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Pairs, in order, the layer paths of the item with the `diff_ids` of its configuration and
+    /// the descriptors recorded in `LayerSources`.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the number of layers of the item differs
+    /// from the number of `diff_ids` of the configuration.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("postgres/manifest.json").unwrap();
+    /// let image_config = image_manifest.0[0].load_configuration("postgres").unwrap();
+    ///
+    /// for layer in image_manifest.0[0].layers_resolved(&image_config).unwrap() {
+    ///     println!("{} -> {}", layer.path(), layer.diff_id());
+    /// }
+    /// ```
+    pub fn layers_resolved(&self, config: &ImageConfiguration) -> ParsleyResult<Vec<Layer>> {
+        config.assert_layer_count(self)?;
+
+        Ok(self
+            .layers
+            .iter()
+            .zip(config.oci_spec().rootfs().diff_ids())
+            .map(|(path, diff_id)| Layer {
+                path: path.clone(),
+                diff_id: diff_id.clone(),
+                descriptor: self.layer_source(diff_id).cloned(),
+            })
+            .collect())
+    }
+
+    /// Attempts to load the configuration referenced by the item from an unpacked `docker save`
+    /// directory.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the configuration file does not exist
+    /// [ParsleyError::Io](ParsleyError::Io) if the configuration file cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("postgres/manifest.json").unwrap();
+    /// let image_config = image_manifest.0[0].load_configuration("postgres").unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn load_configuration<P: AsRef<Path>>(
+        &self,
+        base_dir: P,
+    ) -> ParsleyResult<ImageConfiguration> {
+        ImageConfiguration::from_file(util::path::join_archive_path(base_dir, &self.config))
+            .map_err(|err| match err {
+                ParsleyError::Io(io_err) if io_err.kind() == io::ErrorKind::NotFound => {
+                    docker::Error::from(Error::MissingImageConfiguration).into()
+                }
+                err => err,
+            })
+    }
+
+    /// Converts the item and its blobs into the image manifest expected by the `oci-distribution`
+    /// crate, e.g. for pushing the image to a registry.
+    ///
+    /// See [manifest_item_to_oci_distribution_manifest](
+    /// docker::convert::manifest_item_to_oci_distribution_manifest).
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the number of layers of the item differs
+    /// from the number of `diff_ids` of the configuration or from the number of layer sizes
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::convert::ItemBlobs;
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("postgres/manifest.json").unwrap();
+    /// let blobs = ItemBlobs::from_dir(&image_manifest.0[0], "postgres").unwrap();
+    /// let oci_manifest = image_manifest.0[0].to_oci_distribution_manifest(&blobs).unwrap();
+    /// ```
+    #[cfg(feature = "oci-distribution")]
+    pub fn to_oci_distribution_manifest(
+        &self,
+        blobs: &docker::convert::ItemBlobs,
+    ) -> ParsleyResult<oci_distribution::manifest::OciImageManifest> {
+        docker::convert::manifest_item_to_oci_distribution_manifest(self, blobs)
+    }
+}
+
 /// The `manifest.json` file provides the image JSON for the top-level image and, optionally, for
 /// parent images that this image was derived from.
 ///
@@ -60,6 +335,276 @@ pub struct ManifestItem {
 #[serde(transparent)]
 pub struct ImageManifest(pub Vec<ManifestItem>);
 
+impl ImageManifest {
+    /// Iterates over the items of the manifest.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image::{ImageManifest, ManifestItem};
+    ///
+    /// let image_manifest = ImageManifest(vec![ManifestItem::default()]);
+    ///
+    /// assert_eq!(image_manifest.iter().count(), 1);
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, ManifestItem> {
+        self.0.iter()
+    }
+
+    /// Appends an item to the manifest.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image::{ImageManifest, ManifestItem};
+    ///
+    /// let mut image_manifest = ImageManifest::default();
+    /// image_manifest.push(ManifestItem::default());
+    ///
+    /// assert_eq!(image_manifest.0.len(), 1);
+    /// ```
+    pub fn push(&mut self, item: ManifestItem) {
+        self.0.push(item)
+    }
+
+    /// Removes the first item tagged with `repo_tag` (e.g. `postgres:15.4`), returning it, or
+    /// `None` if no item is tagged with it.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image::{ImageManifest, ManifestItemBuilder};
+    ///
+    /// let item = ManifestItemBuilder::default()
+    ///     .repo_tags(vec!["postgres:15.4".to_owned()])
+    ///     .build()
+    ///     .unwrap();
+    /// let mut image_manifest = ImageManifest(vec![item.clone()]);
+    ///
+    /// assert_eq!(image_manifest.remove_by_repo_tag("postgres:15.4"), Some(item));
+    /// assert!(image_manifest.0.is_empty());
+    /// ```
+    pub fn remove_by_repo_tag(&mut self, repo_tag: &str) -> Option<ManifestItem> {
+        let index = self
+            .0
+            .iter()
+            .position(|item| item.repo_tags.iter().any(|tag| tag == repo_tag))?;
+
+        Some(self.0.remove(index))
+    }
+
+    /// Keeps only the items for which `f` returns `true`, preserving their order.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image::{ImageManifest, ManifestItem};
+    ///
+    /// let mut image_manifest = ImageManifest(vec![ManifestItem::default()]);
+    /// image_manifest.retain(|item| !item.repo_tags().is_empty());
+    ///
+    /// assert!(image_manifest.0.is_empty());
+    /// ```
+    pub fn retain<F: FnMut(&ManifestItem) -> bool>(&mut self, f: F) {
+        self.0.retain(f)
+    }
+
+    /// Layer paths of all items, without duplicates, in the order they are first referenced.
+    ///
+    /// Items built on top of each other share their common layers, which are stored only once
+    /// within the artifact, thus the unique layers account for its storage footprint.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::image::{ImageManifest, ManifestItemBuilder};
+    ///
+    /// let base = ManifestItemBuilder::default()
+    ///     .layers(vec!["a/layer.tar".to_owned()])
+    ///     .build()
+    ///     .unwrap();
+    /// let child = ManifestItemBuilder::default()
+    ///     .layers(vec!["a/layer.tar".to_owned(), "b/layer.tar".to_owned()])
+    ///     .build()
+    ///     .unwrap();
+    /// let image_manifest = ImageManifest(vec![base, child]);
+    ///
+    /// assert_eq!(image_manifest.unique_layers(), ["a/layer.tar", "b/layer.tar"]);
+    /// ```
+    pub fn unique_layers(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+
+        self.0
+            .iter()
+            .flat_map(|item| item.layers().iter().map(String::as_str))
+            .filter(|layer| seen.insert(*layer))
+            .collect()
+    }
+
+    /// Checks that the configuration and the layers of every item exist within an unpacked
+    /// `docker save` directory, catching corrupt or partially extracted archives early.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if a configuration or a layer file does not
+    /// exist, or a layer path is not a regular file.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("postgres/manifest.json").unwrap();
+    /// image_manifest.validate_against_dir("postgres").unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn validate_against_dir<P: AsRef<Path>>(&self, base_dir: P) -> ParsleyResult<()> {
+        let base_dir = base_dir.as_ref();
+
+        for item in &self.0 {
+            if !util::path::join_archive_path(base_dir, item.config()).is_file() {
+                return Err(docker::Error::from(Error::MissingImageConfiguration).into());
+            }
+
+            for layer in item.layers() {
+                let layer_path = util::path::join_archive_path(base_dir, layer);
+
+                if !layer_path.exists() {
+                    return Err(docker::Error::from(Error::MissingLayer(layer.clone())).into());
+                }
+
+                if !layer_path.is_file() {
+                    return Err(docker::Error::from(Error::InvalidLayer(layer.clone())).into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads the configuration of every item from an unpacked `docker save` directory, in order,
+    /// stopping at the first configuration that cannot be loaded.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if a configuration file does not exist
+    /// [ParsleyError::Io](ParsleyError::Io) if a configuration file cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if a configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("postgres/manifest.json").unwrap();
+    ///
+    /// for (item, image_config) in image_manifest.load_all_configurations("postgres").unwrap() {
+    ///     println!("{:?}: {}", item.repo_tags(), image_config.oci_spec().architecture());
+    /// }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn load_all_configurations<P: AsRef<Path>>(
+        &self,
+        base_dir: P,
+    ) -> ParsleyResult<Vec<(ManifestItem, ImageConfiguration)>> {
+        let base_dir = base_dir.as_ref();
+
+        self.0
+            .iter()
+            .map(|item| Ok((item.clone(), item.load_configuration(base_dir)?)))
+            .collect()
+    }
+
+    /// Loads the configuration of every item from an unpacked `docker save` directory, keeping
+    /// only the items whose platform matches `target`.
+    ///
+    /// Operating systems and architectures must be equal, while a missing variant, on either side,
+    /// matches any variant.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if a configuration file does not exist
+    /// [ParsleyError::Io](ParsleyError::Io) if a configuration file cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if a configuration cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::{Arch, Os, PlatformBuilder};
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("postgres/manifest.json").unwrap();
+    /// let target = PlatformBuilder::default()
+    ///     .os(Os::Linux)
+    ///     .architecture(Arch::ARM64)
+    ///     .build()
+    ///     .unwrap();
+    /// let items = image_manifest.filter_by_platform("postgres", &target).unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn filter_by_platform<P: AsRef<Path>>(
+        &self,
+        base_dir: P,
+        target: &oci_spec::image::Platform,
+    ) -> ParsleyResult<Vec<&ManifestItem>> {
+        let base_dir = base_dir.as_ref();
+        let mut items = Vec::new();
+
+        for item in &self.0 {
+            if item
+                .load_configuration(base_dir)?
+                .is_compatible_with(target)
+                .is_ok()
+            {
+                items.push(item);
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+/// # Example
+/// ```
+/// use parsley::docker::image::{ImageManifest, ManifestItem};
+///
+/// let image_manifest = ImageManifest(vec![ManifestItem::default()]);
+///
+/// for item in image_manifest {
+///     println!("{}", item.config());
+/// }
+/// ```
+impl IntoIterator for ImageManifest {
+    type Item = ManifestItem;
+    type IntoIter = std::vec::IntoIter<ManifestItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// # Example
+/// ```
+/// use parsley::docker::image::{ImageManifest, ManifestItem};
+///
+/// let image_manifest = ImageManifest(vec![ManifestItem::default()]);
+///
+/// for item in &image_manifest {
+///     println!("{}", item.config());
+/// }
+/// ```
+impl<'a> IntoIterator for &'a ImageManifest {
+    type Item = &'a ManifestItem;
+    type IntoIter = std::slice::Iter<'a, ManifestItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// # Example
+/// ```
+/// use parsley::docker::image::{ImageManifest, ManifestItem};
+///
+/// let image_manifest: ImageManifest = std::iter::repeat(ManifestItem::default()).take(2).collect();
+///
+/// assert_eq!(image_manifest.0.len(), 2);
+/// ```
+impl FromIterator<ManifestItem> for ImageManifest {
+    fn from_iter<I: IntoIterator<Item = ManifestItem>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 impl FromStr for ImageManifest {
     type Err = ParsleyError;
 
@@ -82,6 +627,52 @@ impl FromStr for ImageManifest {
     }
 }
 
+/// # Example
+/// ``` no_run
+/// use parsley::docker::image;
+///
+/// let bytes = std::fs::read("manifest.json").unwrap();
+/// let image_manifest = image::ImageManifest::try_from(bytes.as_slice()).unwrap();
+/// ```
+impl TryFrom<&[u8]> for ImageManifest {
+    type Error = ParsleyError;
+
+    fn try_from(v: &[u8]) -> ParsleyResult<Self> {
+        Self::from_slice(v)
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use parsley::docker::image;
+///
+/// let s = std::fs::read_to_string("manifest.json").unwrap();
+/// let image_manifest = image::ImageManifest::try_from(s.as_str()).unwrap();
+/// ```
+impl TryFrom<&str> for ImageManifest {
+    type Error = ParsleyError;
+
+    fn try_from(s: &str) -> ParsleyResult<Self> {
+        Self::from_str(s)
+    }
+}
+
+/// # Example
+/// ``` no_run
+/// use std::path::Path;
+/// use parsley::docker::image;
+///
+/// let image_manifest = image::ImageManifest::try_from(Path::new("manifest.json")).unwrap();
+/// ```
+#[cfg(feature = "fs")]
+impl TryFrom<&Path> for ImageManifest {
+    type Error = ParsleyError;
+
+    fn try_from(path: &Path) -> ParsleyResult<Self> {
+        Self::from_file(path)
+    }
+}
+
 impl ImageManifest {
     /// Attempts to load an image manifest from a file.
     ///
@@ -95,10 +686,32 @@ impl ImageManifest {
     ///
     /// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
     /// ```
+    #[cfg(feature = "fs")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
         util::json::from_file(path).map(Self)
     }
 
+    /// Attempts to asynchronously load an image manifest from a file.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file does not exist
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// # async fn load() {
+    /// let image_manifest = image::ImageManifest::from_file_async("manifest.json")
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
+        util::json::from_file_async(path).await
+    }
+
     /// Attempts to load an image manifest from bytes of JSON text.
     ///
     /// # Errors
@@ -114,12 +727,131 @@ impl ImageManifest {
     pub fn from_slice(v: &[u8]) -> ParsleyResult<Self> {
         util::json::from_slice(v)
     }
+
+    /// Attempts to load an image manifest from a stream of JSON text, such as a tar entry or an HTTP body.
+    ///
+    /// The stream is read as is, thus wrapping an unbuffered one in a
+    /// [BufReader](std::io::BufReader) is recommended.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let file = std::fs::File::open("manifest.json").unwrap();
+    /// let image_manifest = image::ImageManifest::from_reader(std::io::BufReader::new(file))
+    ///     .unwrap();
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> ParsleyResult<Self> {
+        util::json::from_reader(reader)
+    }
+
+    /// Parses only the summary of every item out of a stream of manifest JSON text, skipping the
+    /// layers and their descriptors, which dominate the size of large manifests.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the summaries cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let file = std::fs::File::open("manifest.json").unwrap();
+    /// let summaries =
+    ///     image::ImageManifest::summaries_from_reader(std::io::BufReader::new(file)).unwrap();
+    ///
+    /// for summary in summaries {
+    ///     println!("{:?}", summary.repo_tags());
+    /// }
+    /// ```
+    pub fn summaries_from_reader<R: Read>(reader: R) -> ParsleyResult<Vec<ManifestSummary>> {
+        util::json::from_reader(reader)
+    }
+
+    /// Attempts to load an image manifest from a stream of JSON text, reading at most `max_bytes`,
+    /// such that untrusted inputs cannot exhaust the memory.
+    ///
+    /// # Errors
+    /// [ParsleyError::SizeLimitExceeded](ParsleyError::SizeLimitExceeded) if the stream holds more
+    /// than `max_bytes`
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let file = std::fs::File::open("manifest.json").unwrap();
+    /// let image_manifest = image::ImageManifest::from_reader_limited(file, 1 << 20).unwrap();
+    /// ```
+    pub fn from_reader_limited<R: Read>(reader: R, max_bytes: u64) -> ParsleyResult<Self> {
+        util::json::from_reader_limited(reader, max_bytes)
+    }
+
+    /// Attempts to write an image manifest to a file as JSON. If the file already exists, it will
+    /// be overwritten.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the file cannot be created or written
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+    /// image_manifest.to_file("my-manifest.json").unwrap();
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> ParsleyResult<()> {
+        util::json::to_file(self, path)
+    }
+
+    /// Attempts to write an image manifest to a stream as JSON.
+    ///
+    /// # Errors
+    /// [ParsleyError::Io](ParsleyError::Io) if the stream cannot be written
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+    /// let mut writer = Vec::new();
+    /// image_manifest.to_writer(&mut writer).unwrap();
+    /// ```
+    pub fn to_writer<W: Write>(&self, writer: W) -> ParsleyResult<()> {
+        util::json::to_writer(self, writer)
+    }
+
+    /// Attempts to write an image manifest to a string as pretty printed JSON.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be serialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// let image_manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+    /// let json = image_manifest.to_string_pretty().unwrap();
+    /// ```
+    pub fn to_string_pretty(&self) -> ParsleyResult<String> {
+        util::json::to_string_pretty(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "fs")]
     use crate::docker;
+    use test_case::test_case;
 
     fn manifest() -> ImageManifest {
         ImageManifest(vec![ManifestItemBuilder::default()
@@ -139,6 +871,7 @@ mod tests {
             .expect("Manifest Build Item 1")])
     }
 
+    #[cfg(feature = "fs")]
     #[test]
     fn deserialize() {
         let manifest_path = docker::tests::test_data_path("manifest.json");
@@ -152,6 +885,486 @@ mod tests {
         )
     }
 
+    #[cfg(feature = "fs")]
+    #[test]
+    fn from_reader_limited() {
+        let content = std::fs::read(docker::tests::test_data_path("manifest.json"))
+            .expect("Could not read fixture");
+        let size = content.len() as u64;
+
+        assert_eq!(
+            ImageManifest::from_reader_limited(content.as_slice(), size)
+                .expect("Could not deserialize within limit"),
+            manifest()
+        );
+
+        let result = ImageManifest::from_reader_limited(content.as_slice(), size - 1);
+
+        assert!(
+            matches!(result, Err(ParsleyError::SizeLimitExceeded(limit)) if limit == size - 1),
+            "Oversized content was not rejected: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn from_reader() {
+        let content = std::fs::read(docker::tests::test_data_path("manifest.json"))
+            .expect("Could not read manifest fixture");
+        let deserialized_manifest = ImageManifest::from_reader(io::Cursor::new(content))
+            .expect("Could not deserialize from reader");
+
+        assert_eq!(
+            deserialized_manifest,
+            manifest(),
+            "Deserialized manifest does not match expected one"
+        )
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn summaries_from_reader() {
+        let mut json: serde_json::Value = serde_json::from_slice(
+            &std::fs::read(docker::tests::test_data_path("manifest_layer_sources.json"))
+                .expect("Could not read manifest fixture"),
+        )
+        .expect("Invalid manifest fixture");
+
+        // Break the heavy fields, which the summaries must not deserialize
+        json[0]["Layers"] = serde_json::json!({"not": "a list"});
+        json[0]["LayerSources"] = serde_json::json!(["not", "a", "map"]);
+
+        let content = json.to_string();
+
+        assert!(ImageManifest::from_str(&content).is_err());
+
+        let summaries = ImageManifest::summaries_from_reader(io::Cursor::new(content))
+            .expect("Could not deserialize summaries");
+
+        assert_eq!(
+            summaries,
+            [ManifestSummary {
+                config: "b5c9e0a1f3d2c4e6a8b0d2f4e6a8c0e2b4d6f8a0c2e4b6d8f0a2c4e6b8d0f2a4.json"
+                    .to_owned(),
+                repo_tags: vec!["mcr.microsoft.com/windows/nanoserver:ltsc2022".to_owned()],
+                parent: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn layer_dirs() {
+        let manifest = manifest();
+        let item = &manifest.0[0];
+
+        assert_eq!(item.layer_count(), 3, "Unexpected number of layers");
+        assert_eq!(
+            item.layer_dirs().collect::<Vec<_>>(),
+            [
+                "3b05311756d94678c1ea8e45bf7665a4e29f850c31c6f58d6c28403c6fdc0cdc",
+                "454d82adf13f02e53baeae05d06b595b34bbab2836977c6b679488ec038449c3",
+                "c039956656e1c9cd1e2d72dba02179b8d9008e0c0771af344944e218c7dc3351",
+            ]
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn layers_resolved() {
+        let config = ImageConfiguration::from_file(docker::tests::test_data_path("config.json"))
+            .expect("Config fixture");
+        let diff_ids = config.oci_spec().rootfs().diff_ids();
+        let descriptor = oci_spec::image::Descriptor::new(
+            oci_spec::image::MediaType::ImageLayerGzip,
+            1024,
+            "sha256:0e1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8",
+        );
+        let mut item = manifest().0[0].clone();
+
+        item.layer_sources = Some(BTreeMap::from([(diff_ids[0].clone(), descriptor.clone())]));
+
+        let layers = item
+            .layers_resolved(&config)
+            .expect("Could not resolve layers");
+
+        assert_eq!(layers.len(), 3);
+
+        for (index, layer) in layers.iter().enumerate() {
+            assert_eq!(layer.path(), &item.layers()[index]);
+            assert_eq!(layer.diff_id(), &diff_ids[index]);
+        }
+
+        assert_eq!(layers[0].descriptor(), &Some(descriptor));
+        assert!(layers[1..].iter().all(|layer| layer.descriptor().is_none()));
+
+        item.layers.pop();
+
+        let result = item.layers_resolved(&config);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::LayerCountMismatch {
+                        expected: 3,
+                        actual: 2
+                    }
+                )))
+            ),
+            "Layer count mismatch was not reported: {result:?}"
+        );
+    }
+
+    #[test_case(
+        "ee56d70bcdf1aeca472a9899de653eb4d72f4a3ac31d9b0b95e677488ce766f3.json"
+            => Some("sha256:ee56d70bcdf1aeca472a9899de653eb4d72f4a3ac31d9b0b95e677488ce766f3".to_owned());
+        "Legacy layout"
+    )]
+    #[test_case(
+        "blobs/sha256/ee56d70bcdf1aeca472a9899de653eb4d72f4a3ac31d9b0b95e677488ce766f3"
+            => Some("sha256:ee56d70bcdf1aeca472a9899de653eb4d72f4a3ac31d9b0b95e677488ce766f3".to_owned());
+        "OCI layout"
+    )]
+    #[test_case("config.json" => None; "Not a digest")]
+    fn expected_config_digest(config: &str) -> Option<String> {
+        ManifestItemBuilder::default()
+            .config(config)
+            .build()
+            .expect("Build manifest item")
+            .expected_config_digest()
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn verify_config() {
+        let config = std::fs::read(docker::tests::test_data_path(
+            docker::tests::CONTENT_ADDRESSED_CONFIG_PATH,
+        ))
+        .expect("Config fixture");
+        let mut item = manifest().0[0].clone();
+
+        item.config = docker::tests::CONTENT_ADDRESSED_CONFIG_PATH.to_owned();
+        item.verify_config(&config)
+            .expect("Matching digest was rejected");
+
+        let result = manifest().0[0].verify_config(&config);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::ConfigDigestMismatch { ref expected, ref actual }
+                ))) if *expected == format!("sha256:{}", docker::tests::CONFIG_PATH.trim_end_matches(".json"))
+                    && *actual == format!(
+                        "sha256:{}",
+                        docker::tests::CONTENT_ADDRESSED_CONFIG_PATH.trim_end_matches(".json")
+                    )
+            ),
+            "Digest mismatch was not reported: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn push_and_remove() {
+        let mut parent_manifest =
+            ImageManifest::from_file(docker::tests::test_data_path("manifest_parent.json"))
+                .expect("Could not deserialize from file");
+        let item = ManifestItemBuilder::default()
+            .config("config.json")
+            .repo_tags(vec!["busybox:latest".to_owned()])
+            .build()
+            .expect("Build manifest item");
+
+        parent_manifest.push(item.clone());
+
+        assert_eq!(parent_manifest.0.len(), 3);
+        assert_eq!(parent_manifest.0.last(), Some(&item));
+
+        let removed = parent_manifest
+            .remove_by_repo_tag("postgres:15.4")
+            .expect("Missing tagged item");
+
+        assert_eq!(removed.repo_tags(), &["postgres:15.4"]);
+        assert_eq!(parent_manifest.0.len(), 2);
+        assert!(parent_manifest
+            .remove_by_repo_tag("postgres:15.4")
+            .is_none());
+
+        parent_manifest.retain(|item| item.parent().is_none());
+
+        assert_eq!(parent_manifest, ImageManifest(vec![item]));
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn unique_layers() {
+        let parent_manifest =
+            ImageManifest::from_file(docker::tests::test_data_path("manifest_parent.json"))
+                .expect("Could not deserialize from file");
+
+        assert_eq!(
+            parent_manifest
+                .iter()
+                .map(ManifestItem::layer_count)
+                .sum::<usize>(),
+            7,
+            "Unexpected number of layers"
+        );
+        assert_eq!(
+            parent_manifest.unique_layers(),
+            [
+                "3b05311756d94678c1ea8e45bf7665a4e29f850c31c6f58d6c28403c6fdc0cdc/layer.tar",
+                "454d82adf13f02e53baeae05d06b595b34bbab2836977c6b679488ec038449c3/layer.tar",
+                "c039956656e1c9cd1e2d72dba02179b8d9008e0c0771af344944e218c7dc3351/layer.tar",
+                "7e9c1a3b5d7f9e2c4a6b8d0f1e3c5a7b9d2f4e6c8a0b1d3f5e7c9a2b4d6f8e0a/layer.tar",
+            ]
+        );
+        assert!(ImageManifest::default().unique_layers().is_empty());
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn layer_sources() {
+        let foreign_manifest =
+            ImageManifest::from_file(docker::tests::test_data_path("manifest_layer_sources.json"))
+                .expect("Could not deserialize from file");
+        let item = &foreign_manifest.0[0];
+        let layer = "sha256:6d4e4b3aa4f4e7e6b1c0d7a2f5e9c3b8a1d6f0e4c7b2a9d5e8f1c3b6a0d4e7f2";
+        let descriptor = item.layer_source(layer).expect("Missing layer source");
+
+        assert_eq!(
+            descriptor.media_type(),
+            &oci_spec::image::MediaType::Other(
+                "application/vnd.docker.image.rootfs.foreign.diff.tar.gzip".to_owned()
+            )
+        );
+        assert_eq!(descriptor.size(), 117256132);
+        assert!(item.layer_source("sha256:missing").is_none());
+        assert_eq!(
+            item.layer_sources_iter()
+                .map(|(layer, _)| layer)
+                .collect::<Vec<_>>(),
+            [layer]
+        );
+        assert_eq!(manifest().0[0].layer_sources_iter().count(), 0);
+        assert!(manifest().0[0].layer_source(layer).is_none());
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn layer_annotations() {
+        let annotated_manifest = ImageManifest::from_file(docker::tests::test_data_path(
+            "manifest_annotated_layer_sources.json",
+        ))
+        .expect("Could not deserialize from file");
+        let item = &annotated_manifest.0[0];
+        let annotations = item
+            .layer_annotations(
+                "sha256:6d4e4b3aa4f4e7e6b1c0d7a2f5e9c3b8a1d6f0e4c7b2a9d5e8f1c3b6a0d4e7f2",
+            )
+            .expect("Missing annotations");
+
+        assert_eq!(
+            annotations
+                .get("org.opencontainers.image.title")
+                .map(String::as_str),
+            Some("servercore base layer")
+        );
+        assert_eq!(
+            annotations
+                .get("org.opencontainers.image.version")
+                .map(String::as_str),
+            Some("10.0.20348.2113")
+        );
+        assert!(item
+            .layer_annotations(
+                "sha256:9a1c3e5b7d9f2a4c6e8b0d1f3a5c7e9b2d4f6a8c0e1b3d5f7a9c2e4b6d8f0a1c"
+            )
+            .is_none());
+        assert!(item.layer_annotations("sha256:missing").is_none());
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn validate_against_dir() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let manifest = manifest();
+
+        docker::tests::test_image_dir(dir.path());
+        manifest
+            .validate_against_dir(dir.path())
+            .expect("Complete directory was rejected");
+
+        let missing_layer = &manifest.0[0].layers()[1];
+
+        std::fs::remove_file(dir.path().join(missing_layer)).expect("Could not remove layer");
+
+        let result = manifest.validate_against_dir(dir.path());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::MissingLayer(ref layer)
+                ))) if layer == missing_layer
+            ),
+            "Missing layer was not reported: {result:?}"
+        );
+
+        std::fs::create_dir(dir.path().join(missing_layer)).expect("Could not create directory");
+
+        let result = manifest.validate_against_dir(dir.path());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::InvalidLayer(ref layer)
+                ))) if layer == missing_layer
+            ),
+            "Invalid layer was not reported: {result:?}"
+        );
+
+        std::fs::remove_file(dir.path().join(docker::tests::CONFIG_PATH))
+            .expect("Could not remove configuration");
+
+        let result = manifest.validate_against_dir(dir.path());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::MissingImageConfiguration
+                )))
+            ),
+            "Missing configuration was not reported: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    /// Populates `dir` with a manifest whose items reference an `arm64/v8`, an `arm64/v7` and an
+    /// `amd64` configuration
+    fn mixed_platform_dir(dir: &Path) -> ImageManifest {
+        let items = [
+            ("arm64-v8.json", oci_spec::image::Arch::ARM64, "v8"),
+            ("arm64-v7.json", oci_spec::image::Arch::ARM64, "v7"),
+            ("amd64.json", oci_spec::image::Arch::Amd64, ""),
+        ]
+        .into_iter()
+        .map(|(config_path, architecture, variant)| {
+            let mut config =
+                ImageConfiguration::from_file(docker::tests::test_data_path("config.json"))
+                    .expect("Config fixture");
+
+            config.oci_spec_mut().set_architecture(architecture);
+            config
+                .oci_spec_mut()
+                .set_variant((!variant.is_empty()).then(|| variant.to_owned()));
+            config
+                .to_file(dir.join(config_path))
+                .expect("Could not write configuration");
+
+            ManifestItemBuilder::default()
+                .config(config_path)
+                .build()
+                .expect("Build manifest item")
+        })
+        .collect();
+
+        ImageManifest(items)
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn load_all_configurations() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let manifest = mixed_platform_dir(dir.path());
+        let configurations = manifest
+            .load_all_configurations(dir.path())
+            .expect("Could not load configurations");
+
+        assert_eq!(
+            configurations
+                .iter()
+                .map(|(item, config)| (
+                    item.config().as_str(),
+                    config.oci_spec().architecture().clone()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("arm64-v8.json", oci_spec::image::Arch::ARM64),
+                ("arm64-v7.json", oci_spec::image::Arch::ARM64),
+                ("amd64.json", oci_spec::image::Arch::Amd64),
+            ]
+        );
+
+        std::fs::remove_file(dir.path().join("arm64-v7.json")).expect("Could not remove config");
+
+        let result = manifest.load_all_configurations(dir.path());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::MissingImageConfiguration
+                )))
+            ),
+            "Missing configuration was not reported: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test_case(oci_spec::image::Arch::ARM64, Some("v8"), &["arm64-v8.json"]; "Exact")]
+    #[test_case(oci_spec::image::Arch::ARM64, None, &["arm64-v8.json", "arm64-v7.json"]; "Any variant")]
+    #[test_case(oci_spec::image::Arch::Amd64, Some("v3"), &["amd64.json"]; "Missing variant")]
+    #[test_case(oci_spec::image::Arch::s390x, None, &[]; "No match")]
+    fn filter_by_platform(
+        architecture: oci_spec::image::Arch,
+        variant: Option<&str>,
+        expected: &[&str],
+    ) {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let manifest = mixed_platform_dir(dir.path());
+        let mut target = oci_spec::image::Platform::default();
+
+        target.set_os(oci_spec::image::Os::Linux);
+        target.set_architecture(architecture);
+        target.set_variant(variant.map(str::to_owned));
+
+        assert_eq!(
+            manifest
+                .filter_by_platform(dir.path(), &target)
+                .expect("Could not filter manifest")
+                .iter()
+                .map(|item| item.config().as_str())
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn try_from() {
+        let path = docker::tests::test_data_path("manifest.json");
+        let content = std::fs::read_to_string(&path).expect("Could not read fixture");
+        let expected = manifest();
+
+        assert_eq!(
+            ImageManifest::try_from(content.as_bytes()).expect("Could not convert from bytes"),
+            expected
+        );
+        assert_eq!(
+            ImageManifest::try_from(content.as_str()).expect("Could not convert from string"),
+            expected
+        );
+        assert_eq!(
+            ImageManifest::try_from(path.as_path()).expect("Could not convert from path"),
+            expected
+        );
+    }
+
+    #[cfg(feature = "fs")]
     #[test]
     fn serde() {
         let manifest_path = docker::tests::test_data_path("manifest.json");
@@ -167,4 +1380,103 @@ mod tests {
             "Deserialized manifest from serialized manifest is different"
         )
     }
+
+    #[test]
+    fn iterate() {
+        let manifest = manifest();
+        let by_ref = (&manifest).into_iter().cloned().collect::<Vec<_>>();
+        let collected = manifest.clone().into_iter().collect::<ImageManifest>();
+
+        assert_eq!(by_ref, manifest.0);
+        assert_eq!(collected, manifest);
+        assert_eq!(manifest.iter().count(), 1);
+    }
+
+    #[test]
+    fn to_string_pretty() {
+        let deserialized_manifest = manifest();
+        let serialized_manifest = deserialized_manifest
+            .to_string_pretty()
+            .expect("Failed to serialize");
+        let re_deserialized_manifest = ImageManifest::from_str(&serialized_manifest)
+            .expect("Could not deserialize from serialization");
+
+        assert!(
+            serialized_manifest.contains("\"RepoTags\""),
+            "Serialized manifest does not use PascalCase field names"
+        );
+        assert_eq!(
+            re_deserialized_manifest, deserialized_manifest,
+            "Deserialized manifest from pretty serialized manifest is different"
+        )
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn load_configuration() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        docker::tests::test_image_dir(dir.path());
+
+        assert_eq!(
+            manifest().0[0]
+                .load_configuration(dir.path())
+                .expect("Could not load configuration"),
+            ImageConfiguration::from_file(docker::tests::test_data_path("config.json"))
+                .expect("Config fixture"),
+            "Loaded configuration does not match expected one"
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn load_missing_configuration() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let result = manifest().0[0].load_configuration(dir.path());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::MissingImageConfiguration
+                )))
+            ),
+            "Missing configuration was not reported: {result:?}"
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn from_file_async() {
+        let manifest_path = docker::tests::test_data_path("manifest.json");
+        let deserialized_manifest = ImageManifest::from_file_async(manifest_path)
+            .await
+            .expect("Could not deserialize from file");
+
+        assert_eq!(
+            deserialized_manifest,
+            manifest(),
+            "Deserialized manifest does not match expected one"
+        )
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn to_file() {
+        let manifest_path = docker::tests::test_data_path("manifest.json");
+        let deserialized_manifest =
+            ImageManifest::from_file(manifest_path).expect("Could not deserialize from file");
+        let output_dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let output_path = output_dir.path().join("manifest.json");
+
+        deserialized_manifest
+            .to_file(&output_path)
+            .expect("Could not serialize to file");
+        let re_deserialized_manifest = ImageManifest::from_file(&output_path)
+            .expect("Could not deserialize from written file");
+
+        assert_eq!(
+            re_deserialized_manifest, deserialized_manifest,
+            "Deserialized manifest from written file is different"
+        )
+    }
 }
@@ -2,7 +2,7 @@
 //! and definitions.
 
 mod config;
-pub(crate) mod error;
+pub mod error;
 pub(crate) mod manifest;
 
 pub use config::*;
@@ -0,0 +1,202 @@
+//! Layers, as stored by `docker save` archives, OCI image layouts and registries.
+
+use crate::error::ParsleyResult;
+use sha2::{Digest, Sha256};
+use std::io::{self, Read};
+
+/// Magic bytes starting a gzip stream.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// Magic bytes starting a zstd frame.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Number of leading bytes enough to detect any compression.
+pub(crate) const MAGIC_LEN: usize = ZSTD_MAGIC.len();
+
+/// Compression applied to a layer.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Compression {
+    /// Plain tar, as stored by `docker save` archives.
+    None,
+    /// Gzip compressed tar.
+    Gzip,
+    /// Zstd compressed tar.
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression of a layer from its first bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::layer::Compression;
+    ///
+    /// assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+    /// assert_eq!(Compression::detect(b"layer"), Compression::None);
+    /// ```
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Self::Gzip
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Wraps a layer into a reader over its uncompressed tar, detecting the compression from the magic
+/// bytes of the layer.
+///
+/// Since the `diff_ids` of an image configuration are computed over the uncompressed tars, this
+/// allows hashing layers regardless of their source.
+///
+/// # Errors
+/// [ParsleyError::Io](crate::ParsleyError::Io) if the layer cannot be read, or is gzip (resp. zstd)
+/// compressed while the `gzip` (resp. `zstd`) feature is disabled.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::image;
+/// use parsley::docker::layer;
+///
+/// let image_config = image::ImageConfiguration::from_file("config.json").unwrap();
+/// let layer = std::fs::File::open("layer.tar.gz").unwrap();
+///
+/// image_config
+///     .verify_diff_ids([layer::decompressed_reader(layer).unwrap()].into_iter())
+///     .unwrap();
+/// ```
+pub fn decompressed_reader<R: Read + 'static>(mut reader: R) -> ParsleyResult<Box<dyn Read>> {
+    let mut magic = Vec::with_capacity(MAGIC_LEN);
+
+    reader
+        .by_ref()
+        .take(MAGIC_LEN as u64)
+        .read_to_end(&mut magic)?;
+
+    let compression = Compression::detect(&magic);
+    // Put the sniffed bytes back in front of the rest of the layer
+    let reader = io::Cursor::new(magic).chain(reader);
+
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        #[cfg(not(feature = "gzip"))]
+        Compression::Gzip => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "gzip compressed layers require the gzip feature",
+            )
+            .into())
+        }
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "zstd compressed layers require the zstd feature",
+            )
+            .into())
+        }
+    })
+}
+
+/// Computes the `sha256:...` digest of a content, streaming it through the hasher in fixed-size
+/// chunks rather than loading it into memory.
+///
+/// Hashing an uncompressed layer yields its diff id, while hashing a compressed one yields the
+/// digest of its blob.
+///
+/// # Errors
+/// [ParsleyError::Io](crate::ParsleyError::Io) if the content cannot be read.
+///
+/// # Example
+/// ```
+/// use parsley::docker::layer;
+///
+/// assert_eq!(
+///     layer::hash_reader(&b""[..]).unwrap(),
+///     "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+/// );
+/// ```
+pub fn hash_reader<R: Read>(mut reader: R) -> ParsleyResult<String> {
+    let mut hasher = Sha256::new();
+
+    io::copy(&mut reader, &mut hasher)?;
+
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker;
+    use test_case::test_case;
+
+    #[cfg(feature = "gzip")]
+    fn gzip_compress(content: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+
+        io::Write::write_all(&mut encoder, content).expect("Could not compress");
+        encoder.finish().expect("Could not compress")
+    }
+
+    #[cfg(feature = "zstd")]
+    fn zstd_compress(content: &[u8]) -> Vec<u8> {
+        zstd::stream::encode_all(content, 0).expect("Could not compress")
+    }
+
+    #[test_case(<[u8]>::to_vec, Compression::None; "Uncompressed")]
+    #[cfg_attr(feature = "gzip", test_case(gzip_compress, Compression::Gzip; "Gzip"))]
+    #[cfg_attr(feature = "zstd", test_case(zstd_compress, Compression::Zstd; "Zstd"))]
+    fn decompressed_reader(compress: fn(&[u8]) -> Vec<u8>, compression: Compression) {
+        let content = docker::tests::layer_content("layer");
+        let compressed = compress(&content);
+        let mut decompressed = Vec::new();
+
+        assert_eq!(Compression::detect(&compressed), compression);
+
+        super::decompressed_reader(io::Cursor::new(compressed))
+            .expect("Could not open layer")
+            .read_to_end(&mut decompressed)
+            .expect("Could not read layer");
+
+        assert_eq!(decompressed, content, "Decompressed layer differs");
+    }
+
+    #[test_case(b"", "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"; "Empty")]
+    #[test_case(b"abc", "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"; "Short")]
+    fn hash_reader(content: &[u8], expected: &str) {
+        assert_eq!(
+            super::hash_reader(content).expect("Could not hash content"),
+            expected
+        );
+    }
+
+    #[test]
+    fn hash_reader_large() {
+        // Larger than the copy buffer, such that the content is hashed over several chunks
+        let content = vec![0x5a; 1 << 20];
+
+        assert_eq!(
+            super::hash_reader(content.as_slice()).expect("Could not hash content"),
+            crate::docker::digest::Digest::sha256(&content).to_string()
+        );
+    }
+
+    #[test_case(&[]; "Empty")]
+    #[test_case(&[0x1f]; "Shorter than magic")]
+    fn short_layer(content: &[u8]) {
+        let mut decompressed = Vec::new();
+
+        super::decompressed_reader(io::Cursor::new(content.to_vec()))
+            .expect("Could not open layer")
+            .read_to_end(&mut decompressed)
+            .expect("Could not read layer");
+
+        assert_eq!(decompressed, content, "Short layer was altered");
+    }
+}
@@ -0,0 +1,144 @@
+use crate::docker;
+use crate::docker::distribution;
+use crate::docker::image::error::Error;
+use crate::error::ParsleyResult;
+use crate::util;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+/// Dialect of a `manifest.json` file, telling which parser it goes to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ManifestKind {
+    /// The array produced by `docker save`, parsed by
+    /// [ImageManifest](crate::docker::image::ImageManifest).
+    DockerSave,
+    /// A registry image manifest, parsed by
+    /// [RegistryManifest](crate::docker::distribution::RegistryManifest).
+    RegistryImage,
+    /// A registry image index (manifest list), parsed by
+    /// [RegistryManifest](crate::docker::distribution::RegistryManifest).
+    RegistryIndex,
+}
+
+impl ManifestKind {
+    /// Detects the dialect of a manifest held in bytes of JSON text.
+    ///
+    /// # Errors
+    /// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if the content is not JSON
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the shape matches no dialect.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::docker::ManifestKind;
+    ///
+    /// assert_eq!(
+    ///     ManifestKind::from_slice(br#"[{"Config": "config.json"}]"#).unwrap(),
+    ///     ManifestKind::DockerSave
+    /// );
+    /// ```
+    pub fn from_slice(v: &[u8]) -> ParsleyResult<Self> {
+        Self::detect(&util::json::from_slice(v)?)
+    }
+
+    /// Detects the dialect of a manifest out of its top-level JSON shape.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the shape matches no dialect.
+    fn detect(json: &serde_json::Value) -> ParsleyResult<Self> {
+        match json {
+            serde_json::Value::Array(_) => Ok(Self::DockerSave),
+            serde_json::Value::Object(object) if object.contains_key("schemaVersion") => {
+                if distribution::is_index(object) {
+                    Ok(Self::RegistryIndex)
+                } else {
+                    Ok(Self::RegistryImage)
+                }
+            }
+            _ => Err(docker::Error::from(Error::InvalidImageManifest).into()),
+        }
+    }
+}
+
+/// Detects whether a `manifest.json` file is a `docker save` manifest or a registry one, by
+/// peeking at its top-level JSON shape: an array or an object with a `schemaVersion`.
+///
+/// # Errors
+/// [ParsleyError::Io](crate::ParsleyError::Io) if the file cannot be read
+/// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if the file is not JSON
+/// [ParsleyError::Docker](crate::ParsleyError::Docker) if the shape matches no dialect.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::{self, distribution, image, ManifestKind};
+///
+/// match docker::detect_manifest_kind("manifest.json").unwrap() {
+///     ManifestKind::DockerSave => {
+///         let manifest = image::ImageManifest::from_file("manifest.json").unwrap();
+///     }
+///     ManifestKind::RegistryImage | ManifestKind::RegistryIndex => {
+///         let manifest = distribution::RegistryManifest::from_file("manifest.json").unwrap();
+///     }
+/// }
+/// ```
+#[cfg(feature = "fs")]
+pub fn detect_manifest_kind<P: AsRef<Path>>(path: P) -> ParsleyResult<ManifestKind> {
+    ManifestKind::detect(&util::json::from_file(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParsleyError;
+    use test_case::test_case;
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn docker_save() {
+        assert_eq!(
+            detect_manifest_kind(docker::tests::test_data_path("manifest.json"))
+                .expect("Could not detect manifest kind"),
+            ManifestKind::DockerSave
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test_case(
+        r#"{"schemaVersion": 2, "mediaType": "application/vnd.oci.image.manifest.v1+json", "layers": []}"#,
+        ManifestKind::RegistryImage;
+        "Image manifest"
+    )]
+    #[test_case(r#"{"schemaVersion": 2, "manifests": []}"#, ManifestKind::RegistryIndex; "Image index")]
+    #[test_case(
+        r#"{"schemaVersion": 2, "mediaType": "application/vnd.docker.distribution.manifest.list.v2+json"}"#,
+        ManifestKind::RegistryIndex;
+        "Manifest list media type"
+    )]
+    fn registry(content: &str, expected: ManifestKind) {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let path = dir.path().join("manifest.json");
+
+        std::fs::write(&path, content).expect("Could not write manifest");
+
+        assert_eq!(
+            detect_manifest_kind(&path).expect("Could not detect manifest kind"),
+            expected
+        );
+    }
+
+    #[test_case(r#"{"Config": "config.json"}"#; "Object without schema version")]
+    #[test_case(r#""manifest""#; "String")]
+    fn unknown(content: &str) {
+        let json = serde_json::from_str(content).expect("Invalid JSON");
+        let result = ManifestKind::detect(&json);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(
+                    Error::InvalidImageManifest
+                )))
+            ),
+            "Unknown shape was detected: {result:?}"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Media types of the documents and blobs of Docker and OCI images.
+
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Media type of a document or blob, covering the common Docker and OCI variants.
+///
+/// Media types not covered by a variant are kept as is by [Other](MediaType::Other), such that
+/// parsing never fails and every media type round trips.
+///
+/// # Example
+/// ```
+/// use std::str::FromStr;
+/// use parsley::docker::media_type::MediaType;
+///
+/// let media_type =
+///     MediaType::from_str("application/vnd.docker.container.image.v1+json").unwrap();
+///
+/// assert_eq!(media_type, MediaType::DockerConfig);
+/// assert_eq!(
+///     MediaType::OciLayerGzip.to_string(),
+///     "application/vnd.oci.image.layer.v1.tar+gzip"
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MediaType {
+    /// Docker image manifest (schema 2).
+    DockerManifest,
+    /// Docker manifest list, pointing to an image manifest per platform.
+    DockerManifestList,
+    /// Docker image configuration.
+    DockerConfig,
+    /// Uncompressed Docker layer.
+    DockerLayer,
+    /// Gzip compressed Docker layer.
+    DockerLayerGzip,
+    /// OCI image manifest.
+    OciManifest,
+    /// OCI image index, pointing to an image manifest per platform.
+    OciIndex,
+    /// OCI image configuration.
+    OciConfig,
+    /// Uncompressed OCI layer.
+    OciLayer,
+    /// Gzip compressed OCI layer.
+    OciLayerGzip,
+    /// Zstd compressed OCI layer.
+    OciLayerZstd,
+    /// Any other media type.
+    Other(String),
+}
+
+impl MediaType {
+    /// The media type string.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::DockerManifest => "application/vnd.docker.distribution.manifest.v2+json",
+            Self::DockerManifestList => "application/vnd.docker.distribution.manifest.list.v2+json",
+            Self::DockerConfig => "application/vnd.docker.container.image.v1+json",
+            Self::DockerLayer => "application/vnd.docker.image.rootfs.diff.tar",
+            Self::DockerLayerGzip => "application/vnd.docker.image.rootfs.diff.tar.gzip",
+            Self::OciManifest => "application/vnd.oci.image.manifest.v1+json",
+            Self::OciIndex => "application/vnd.oci.image.index.v1+json",
+            Self::OciConfig => "application/vnd.oci.image.config.v1+json",
+            Self::OciLayer => "application/vnd.oci.image.layer.v1.tar",
+            Self::OciLayerGzip => "application/vnd.oci.image.layer.v1.tar+gzip",
+            Self::OciLayerZstd => "application/vnd.oci.image.layer.v1.tar+zstd",
+            Self::Other(media_type) => media_type,
+        }
+    }
+
+    /// Whether the media type is the one of a document listing an image manifest per platform.
+    pub fn is_index(&self) -> bool {
+        matches!(self, Self::DockerManifestList | Self::OciIndex)
+    }
+}
+
+impl From<&str> for MediaType {
+    fn from(s: &str) -> Self {
+        match s {
+            "application/vnd.docker.distribution.manifest.v2+json" => Self::DockerManifest,
+            "application/vnd.docker.distribution.manifest.list.v2+json" => Self::DockerManifestList,
+            "application/vnd.docker.container.image.v1+json" => Self::DockerConfig,
+            "application/vnd.docker.image.rootfs.diff.tar" => Self::DockerLayer,
+            "application/vnd.docker.image.rootfs.diff.tar.gzip" => Self::DockerLayerGzip,
+            "application/vnd.oci.image.manifest.v1+json" => Self::OciManifest,
+            "application/vnd.oci.image.index.v1+json" => Self::OciIndex,
+            "application/vnd.oci.image.config.v1+json" => Self::OciConfig,
+            "application/vnd.oci.image.layer.v1.tar" => Self::OciLayer,
+            "application/vnd.oci.image.layer.v1.tar+gzip" => Self::OciLayerGzip,
+            "application/vnd.oci.image.layer.v1.tar+zstd" => Self::OciLayerZstd,
+            media_type => Self::Other(media_type.to_owned()),
+        }
+    }
+}
+
+impl FromStr for MediaType {
+    type Err = Infallible;
+
+    /// Parses a media type, falling back to [Other](MediaType::Other) for unknown ones.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<MediaType> for oci_spec::image::MediaType {
+    fn from(media_type: MediaType) -> Self {
+        Self::from(media_type.as_str())
+    }
+}
+
+impl From<&oci_spec::image::MediaType> for MediaType {
+    fn from(media_type: &oci_spec::image::MediaType) -> Self {
+        Self::from(media_type.to_string().as_str())
+    }
+}
+
+impl Serialize for MediaType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MediaType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let media_type: String = Deserialize::deserialize(deserializer)?;
+
+        Ok(Self::from(media_type.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(MediaType::DockerManifest; "Docker manifest")]
+    #[test_case(MediaType::DockerManifestList; "Docker manifest list")]
+    #[test_case(MediaType::DockerConfig; "Docker config")]
+    #[test_case(MediaType::DockerLayer; "Docker layer")]
+    #[test_case(MediaType::DockerLayerGzip; "Docker gzip layer")]
+    #[test_case(MediaType::OciManifest; "OCI manifest")]
+    #[test_case(MediaType::OciIndex; "OCI index")]
+    #[test_case(MediaType::OciConfig; "OCI config")]
+    #[test_case(MediaType::OciLayer; "OCI layer")]
+    #[test_case(MediaType::OciLayerGzip; "OCI gzip layer")]
+    #[test_case(MediaType::OciLayerZstd; "OCI zstd layer")]
+    #[test_case(MediaType::Other("application/vnd.custom+json".to_owned()); "Other")]
+    fn round_trip(media_type: MediaType) {
+        let serialized = serde_json::to_string(&media_type).expect("Failed to serialize");
+
+        assert_eq!(serialized, format!("\"{media_type}\""));
+        assert_eq!(
+            serde_json::from_str::<MediaType>(&serialized).expect("Failed to deserialize"),
+            media_type,
+            "Media type does not round trip through serde"
+        );
+        assert_eq!(
+            MediaType::from_str(&media_type.to_string()),
+            Ok(media_type.clone()),
+            "Media type does not round trip through strings"
+        );
+        assert_eq!(
+            MediaType::from(&oci_spec::image::MediaType::from(media_type.clone())),
+            media_type,
+            "Media type does not round trip through OCI spec"
+        );
+    }
+}
@@ -1,15 +1,46 @@
+pub mod archive;
+pub mod convert;
+pub mod digest;
 pub mod distribution;
 pub(crate) mod error;
 pub mod image;
+pub mod layer;
+mod manifest_kind;
+pub mod media_type;
+pub mod oci_layout;
+#[cfg(feature = "fs")]
+mod parsed_image;
+pub mod reference;
 
 pub use error::*;
+pub use manifest_kind::*;
+#[cfg(feature = "fs")]
+pub use parsed_image::*;
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    #[cfg(feature = "fs")]
+    use crate::docker::image::ImageManifest;
+    #[cfg(feature = "fs")]
+    use std::fs;
+    #[cfg(feature = "fs")]
     use std::path::Path;
+    #[cfg(feature = "fs")]
     use std::path::PathBuf;
 
+    /// Name of the configuration file referenced by the test manifest
+    #[cfg(feature = "fs")]
+    pub(crate) const CONFIG_PATH: &str =
+        "ee56d70bcdf1aeca472a9899de653eb4d72f4a3ac31d9b0b95e677488ce766f3.json";
+
+    /// Name of a copy of the configuration fixture named after the SHA-256 of its content, as
+    /// written by `docker save`
+    #[cfg(feature = "fs")]
+    pub(crate) const CONTENT_ADDRESSED_CONFIG_PATH: &str =
+        "c20248d4529d29d323063e5c620a8f53529a9ccaf8b08280bc29bdda8a883b46.json";
+
     /// Creates the path to the directory containing Docker test data
+    #[cfg(feature = "fs")]
     pub(crate) fn test_data_path<P>(path: P) -> PathBuf
     where
         P: AsRef<Path>,
@@ -18,4 +49,45 @@ mod tests {
             .join("tests/data/docker")
             .join(path)
     }
+
+    /// Creates the content of a layer: a tarball holding a single file named after the layer
+    pub(crate) fn layer_content(layer_path: &str) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+
+        header.set_size(layer_path.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "layer", layer_path.as_bytes())
+            .expect("Append layer file");
+
+        builder.into_inner().expect("Build layer")
+    }
+
+    /// Populates `dir` with the layout of an unpacked `docker save` archive, out of the Docker
+    /// test data
+    #[cfg(feature = "fs")]
+    pub(crate) fn test_image_dir(dir: &Path) {
+        let manifest_path = test_data_path("manifest.json");
+        let manifest = ImageManifest::from_file(&manifest_path).expect("Manifest fixture");
+
+        for item in &manifest.0 {
+            for layer in item.layers() {
+                let layer_path = crate::util::path::join_archive_path(dir, layer);
+
+                fs::create_dir_all(layer_path.parent().expect("Layer directory"))
+                    .expect("Create layer directory");
+                fs::write(layer_path, layer_content(layer)).expect("Write layer");
+            }
+        }
+
+        fs::copy(test_data_path("config.json"), dir.join(CONFIG_PATH)).expect("Copy config");
+        fs::copy(
+            test_data_path("repositories.json"),
+            dir.join("repositories"),
+        )
+        .expect("Copy repositories");
+        fs::copy(manifest_path, dir.join("manifest.json")).expect("Copy manifest");
+    }
 }
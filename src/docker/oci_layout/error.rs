@@ -0,0 +1,30 @@
+use thiserror::Error;
+
+/// Error type for handling OCI image layout related failures
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Error caused by missing `oci-layout` file
+    #[error("oci-layout file is missing from image layout")]
+    MissingLayoutHeader,
+
+    /// Error caused by missing `index.json` file
+    #[error("index is missing from image layout")]
+    MissingIndex,
+
+    /// Error caused by a descriptor whose blob is missing
+    #[error("blob {0} is missing from image layout")]
+    MissingBlob(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Error::MissingLayoutHeader, "oci-layout file is missing from image layout"; "Missing layout header")]
+    #[test_case(Error::MissingIndex, "index is missing from image layout"; "Missing index")]
+    #[test_case(Error::MissingBlob("sha256:a".to_owned()), "blob sha256:a is missing from image layout"; "Missing blob")]
+    fn display(error: Error, expected: &str) {
+        assert_eq!(error.to_string(), expected);
+    }
+}
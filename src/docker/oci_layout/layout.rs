@@ -0,0 +1,308 @@
+use crate::docker;
+use crate::docker::digest::Digest;
+use crate::docker::image::ImageConfiguration;
+use crate::docker::oci_layout::error::Error;
+use crate::error::{ParsleyError, ParsleyResult};
+use crate::util;
+use derive_builder::Builder;
+use getset::Getters;
+use oci_spec::image::{Descriptor, ImageIndex, ImageManifest};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Name of the file marking the root of an image layout.
+const LAYOUT_HEADER_FILE: &str = "oci-layout";
+
+/// Name of the file holding the entry point of an image layout.
+const INDEX_FILE: &str = "index.json";
+
+/// Name of the directory holding the content addressable blobs of an image layout.
+const BLOBS_DIR: &str = "blobs";
+
+/// Content of the `oci-layout` file, marking the root of an image layout.
+///
+/// # Example
+/// ```
+/// use parsley::docker::oci_layout::LayoutHeaderBuilder;
+///
+/// let header = LayoutHeaderBuilder::default()
+///     .image_layout_version("1.0.0")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Builder, Getters, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "ParsleyError")
+)]
+#[getset(get = "pub")]
+pub struct LayoutHeader {
+    /// Version of the image layout.
+    image_layout_version: String,
+}
+
+/// An [OCI image layout](https://github.com/opencontainers/image-spec/blob/main/image-layout.md)
+/// directory, as produced by `docker save` (starting with Docker 25) or `skopeo`.
+///
+/// The `oci-layout` and `index.json` files are parsed eagerly, while the blobs they reference are
+/// resolved on demand from the `blobs/<algorithm>/<hex>` files.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker::oci_layout::OciLayout;
+///
+/// let layout = OciLayout::from_dir("postgres").unwrap();
+///
+/// for descriptor in layout.index().manifests() {
+///     let manifest = layout.manifest(descriptor).unwrap();
+///     let image_config = layout.configuration(&manifest).unwrap();
+/// }
+/// ```
+#[derive(Getters, Clone, Debug, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct OciLayout {
+    /// Root directory of the image layout.
+    path: PathBuf,
+    /// The `oci-layout` file of the image layout.
+    header: LayoutHeader,
+    /// The `index.json` file of the image layout.
+    index: ImageIndex,
+}
+
+impl OciLayout {
+    /// Attempts to load an image layout from its root directory.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the `oci-layout` or `index.json` file does
+    /// not exist
+    /// [ParsleyError::Io](ParsleyError::Io) if a file cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if a file cannot be deserialized.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::oci_layout::OciLayout;
+    ///
+    /// let layout = OciLayout::from_dir("postgres").unwrap();
+    /// ```
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> ParsleyResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let header = read_json(path.join(LAYOUT_HEADER_FILE), Error::MissingLayoutHeader)?;
+        let index = read_json(path.join(INDEX_FILE), Error::MissingIndex)?;
+
+        Ok(Self {
+            path,
+            header,
+            index,
+        })
+    }
+
+    /// Path of the blob referenced by a descriptor.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the digest of the descriptor is malformed.
+    pub fn blob_path(&self, descriptor: &Descriptor) -> ParsleyResult<PathBuf> {
+        let digest = Digest::from_str(descriptor.digest())?;
+
+        Ok(self
+            .path
+            .join(BLOBS_DIR)
+            .join(digest.algorithm().name())
+            .join(digest.hex()))
+    }
+
+    /// Opens a reader over the blob referenced by a descriptor.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the blob does not exist or the digest of the
+    /// descriptor is malformed
+    /// [ParsleyError::Io](ParsleyError::Io) if the blob cannot be opened.
+    pub fn blob_reader(&self, descriptor: &Descriptor) -> ParsleyResult<impl Read> {
+        fs::File::open(self.blob_path(descriptor)?)
+            .map(io::BufReader::new)
+            .map_err(|err| missing(err.into(), Error::MissingBlob(descriptor.digest().clone())))
+    }
+
+    /// Attempts to load the image manifest referenced by a descriptor of the index.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the blob does not exist
+    /// [ParsleyError::Io](ParsleyError::Io) if the blob cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the manifest cannot be deserialized.
+    pub fn manifest(&self, descriptor: &Descriptor) -> ParsleyResult<ImageManifest> {
+        read_json(
+            self.blob_path(descriptor)?,
+            Error::MissingBlob(descriptor.digest().clone()),
+        )
+    }
+
+    /// Attempts to load the image configuration referenced by an image manifest.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the blob does not exist
+    /// [ParsleyError::Io](ParsleyError::Io) if the blob cannot be read
+    /// [ParsleyError::SerDe](ParsleyError::SerDe) if the configuration cannot be deserialized.
+    pub fn configuration(&self, manifest: &ImageManifest) -> ParsleyResult<ImageConfiguration> {
+        read_json(
+            self.blob_path(manifest.config())?,
+            Error::MissingBlob(manifest.config().digest().clone()),
+        )
+    }
+}
+
+/// Deserializes a JSON file, reporting `missing_error` if the file does not exist.
+fn read_json<P, T>(path: P, missing_error: Error) -> ParsleyResult<T>
+where
+    T: serde::de::DeserializeOwned,
+    P: AsRef<Path>,
+{
+    util::json::from_file(path).map_err(|err| missing(err, missing_error))
+}
+
+/// Replaces a not found IO error with `missing_error`.
+fn missing(err: ParsleyError, missing_error: Error) -> ParsleyError {
+    match err {
+        ParsleyError::Io(io_err) if io_err.kind() == io::ErrorKind::NotFound => {
+            docker::Error::from(missing_error).into()
+        }
+        err => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oci_spec::image::{DescriptorBuilder, ImageIndexBuilder, ImageManifestBuilder, MediaType};
+    use sha2::Sha256;
+
+    /// Writes a blob into the image layout, returning its descriptor
+    fn write_blob(dir: &Path, media_type: MediaType, content: &[u8]) -> Descriptor {
+        let hex = format!("{:x}", <Sha256 as sha2::Digest>::digest(content));
+        let blobs_dir = dir.join(BLOBS_DIR).join("sha256");
+
+        fs::create_dir_all(&blobs_dir).expect("Could not create blobs directory");
+        fs::write(blobs_dir.join(&hex), content).expect("Could not write blob");
+
+        DescriptorBuilder::default()
+            .media_type(media_type)
+            .digest(format!("sha256:{hex}"))
+            .size(content.len() as i64)
+            .build()
+            .expect("Build descriptor")
+    }
+
+    /// Populates `dir` with an image layout holding the Docker test data, returning the manifest
+    fn test_layout(dir: &Path) -> ImageManifest {
+        let config = fs::read(docker::tests::test_data_path("config.json")).expect("Config");
+        let config_descriptor = write_blob(dir, MediaType::ImageConfig, &config);
+        let layer_descriptor = write_blob(
+            dir,
+            MediaType::ImageLayer,
+            &docker::tests::layer_content("layer"),
+        );
+        let manifest = ImageManifestBuilder::default()
+            .schema_version(2_u32)
+            .config(config_descriptor)
+            .layers(vec![layer_descriptor])
+            .build()
+            .expect("Build manifest");
+        let manifest_descriptor = write_blob(
+            dir,
+            MediaType::ImageManifest,
+            &serde_json::to_vec(&manifest).expect("Serialize manifest"),
+        );
+        let index = ImageIndexBuilder::default()
+            .schema_version(2_u32)
+            .manifests(vec![manifest_descriptor])
+            .build()
+            .expect("Build index");
+
+        fs::write(
+            dir.join(LAYOUT_HEADER_FILE),
+            "{\"imageLayoutVersion\": \"1.0.0\"}",
+        )
+        .expect("Could not write layout header");
+        index
+            .to_file(dir.join(INDEX_FILE))
+            .expect("Could not write index");
+
+        manifest
+    }
+
+    #[test]
+    fn from_dir() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let expected_manifest = test_layout(dir.path());
+        let layout = OciLayout::from_dir(dir.path()).expect("Could not read image layout");
+
+        assert_eq!(layout.header().image_layout_version(), "1.0.0");
+        assert_eq!(layout.index().manifests().len(), 1);
+
+        let manifest = layout
+            .manifest(&layout.index().manifests()[0])
+            .expect("Could not read manifest");
+
+        assert_eq!(
+            manifest, expected_manifest,
+            "Manifest does not match expected one"
+        );
+        assert_eq!(
+            layout
+                .configuration(&manifest)
+                .expect("Could not read configuration"),
+            ImageConfiguration::from_file(docker::tests::test_data_path("config.json"))
+                .expect("Config fixture"),
+            "Configuration does not match expected one"
+        );
+
+        let mut layer = Vec::new();
+        layout
+            .blob_reader(&manifest.layers()[0])
+            .expect("Could not open layer")
+            .read_to_end(&mut layer)
+            .expect("Could not read layer");
+
+        assert_eq!(layer, docker::tests::layer_content("layer"));
+    }
+
+    #[test]
+    fn missing_files() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+        let result = OciLayout::from_dir(dir.path());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::OciLayoutError(
+                    Error::MissingLayoutHeader
+                )))
+            ),
+            "Missing layout header was not reported: {result:?}"
+        );
+
+        let manifest = test_layout(dir.path());
+        let layout = OciLayout::from_dir(dir.path()).expect("Could not read image layout");
+        fs::remove_file(
+            layout
+                .blob_path(manifest.config())
+                .expect("Could not resolve configuration"),
+        )
+        .expect("Could not remove configuration");
+        let result = layout.configuration(&manifest);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::OciLayoutError(
+                    Error::MissingBlob(ref digest)
+                ))) if digest == manifest.config().digest()
+            ),
+            "Missing blob was not reported: {result:?}"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! [OCI Image Layout Specification](https://github.com/opencontainers/image-spec/blob/main/image-layout.md)
+//! types and definitions.
+
+pub mod error;
+#[cfg(feature = "fs")]
+mod layout;
+
+#[cfg(feature = "fs")]
+pub use layout::*;
@@ -0,0 +1,265 @@
+use crate::docker;
+use crate::docker::distribution::Repositories;
+use crate::docker::image::{self, ImageConfiguration, ImageManifest, ManifestItem};
+use crate::error::ParsleyResult;
+use getset::Getters;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Name of the file holding the image manifest.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Name of the file holding the repositories data.
+const REPOSITORIES_FILE: &str = "repositories";
+
+/// Everything an unpacked `docker save` directory describes, as loaded by
+/// [parse_image_dir](parse_image_dir).
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker;
+///
+/// let parsed_image = docker::parse_image_dir("postgres").unwrap();
+///
+/// for item in parsed_image.manifest().iter() {
+///     let image_config = parsed_image.configuration(item).unwrap();
+/// }
+/// ```
+#[derive(Getters, Clone, Debug, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct ParsedImage {
+    /// Path to the directory on disk.
+    path: PathBuf,
+    /// The `manifest.json` file of the directory.
+    manifest: ImageManifest,
+    /// Configurations referenced by the manifest, keyed by their path within the directory.
+    configurations: BTreeMap<String, ImageConfiguration>,
+    /// The `repositories` file of the directory, if present.
+    repositories: Option<Repositories>,
+}
+
+/// Aggregated figures of a [ParsedImage](ParsedImage), as returned by
+/// [ParsedImage::stats](ParsedImage::stats).
+#[derive(Getters, Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct ImageStats {
+    /// Number of distinct layers referenced by the manifest.
+    layer_count: usize,
+    /// Number of distinct tags referenced by the manifest.
+    tag_count: usize,
+    /// Number of loaded configurations.
+    configuration_count: usize,
+    /// Total size in bytes of the layers described by `LayerSources`, if any.
+    size: Option<u64>,
+}
+
+impl ParsedImage {
+    /// Aggregates the number of layers, tags and configurations of the image, along with the
+    /// total size of the layers whose descriptor is recorded in `LayerSources`.
+    ///
+    /// Layers shared by several manifest items are counted once. The size is `None` when no
+    /// item records layer sources, the sizes of the other layers being unknown without reading
+    /// them.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker;
+    ///
+    /// let stats = docker::parse_image_dir("postgres").unwrap().stats();
+    ///
+    /// println!("{} layers, {} tags", stats.layer_count(), stats.tag_count());
+    /// ```
+    pub fn stats(&self) -> ImageStats {
+        let tags = self
+            .manifest
+            .iter()
+            .flat_map(|item| item.repo_tags())
+            .collect::<BTreeSet<_>>();
+        let layer_sources = self
+            .manifest
+            .iter()
+            .flat_map(|item| item.layer_sources_iter())
+            .collect::<BTreeMap<_, _>>();
+
+        ImageStats {
+            layer_count: self.manifest.unique_layers().len(),
+            tag_count: tags.len(),
+            configuration_count: self.configurations.len(),
+            size: (!layer_sources.is_empty()).then(|| {
+                layer_sources
+                    .values()
+                    .map(|descriptor| u64::try_from(descriptor.size()).unwrap_or_default())
+                    .sum()
+            }),
+        }
+    }
+
+    /// Retrieves the configuration referenced by a manifest item.
+    ///
+    /// # Errors
+    /// [ParsleyError::Docker](crate::ParsleyError::Docker) if the configuration is not referenced
+    /// by the manifest of the directory.
+    pub fn configuration(&self, item: &ManifestItem) -> ParsleyResult<&ImageConfiguration> {
+        Ok(self
+            .configurations
+            .get(item.config())
+            .ok_or(docker::Error::from(
+                image::error::Error::MissingImageConfiguration,
+            ))?)
+    }
+}
+
+/// Loads the manifest, every configuration it references and, if present, the repositories data
+/// of an unpacked `docker save` directory.
+///
+/// Layers are not read, their paths being available through the manifest.
+///
+/// # Errors
+/// [ParsleyError::Docker](crate::ParsleyError::Docker) if the manifest or a configuration it
+/// references does not exist, or the repositories data is malformed
+/// [ParsleyError::Io](crate::ParsleyError::Io) if a file cannot be read
+/// [ParsleyError::SerDe](crate::ParsleyError::SerDe) if a file cannot be deserialized.
+///
+/// # Example
+/// ``` no_run
+/// use parsley::docker;
+///
+/// let parsed_image = docker::parse_image_dir("postgres").unwrap();
+///
+/// println!("{} images", parsed_image.manifest().0.len());
+/// ```
+pub fn parse_image_dir<P: AsRef<Path>>(path: P) -> ParsleyResult<ParsedImage> {
+    let path = path.as_ref().to_path_buf();
+    let manifest_path = path.join(MANIFEST_FILE);
+
+    if !manifest_path.is_file() {
+        return Err(docker::Error::from(image::error::Error::MissingImageManifest).into());
+    }
+
+    let manifest = ImageManifest::from_file(manifest_path)?;
+    let mut configurations = BTreeMap::new();
+
+    for item in manifest.iter() {
+        configurations.insert(item.config().to_owned(), item.load_configuration(&path)?);
+    }
+
+    let repositories_path = path.join(REPOSITORIES_FILE);
+    let repositories = if repositories_path.is_file() {
+        Some(Repositories::from_file(repositories_path)?)
+    } else {
+        None
+    };
+
+    Ok(ParsedImage {
+        path,
+        manifest,
+        configurations,
+        repositories,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParsleyError;
+    use std::fs;
+    use test_case::test_case;
+
+    #[test]
+    fn parse_image_dir() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+
+        docker::tests::test_image_dir(dir.path());
+
+        let parsed_image = super::parse_image_dir(dir.path()).expect("Could not parse directory");
+        let item = &parsed_image.manifest().0[0];
+
+        assert_eq!(parsed_image.path(), dir.path());
+        assert_eq!(
+            parsed_image.manifest(),
+            &ImageManifest::from_file(docker::tests::test_data_path("manifest.json"))
+                .expect("Manifest fixture")
+        );
+        assert_eq!(
+            parsed_image
+                .configuration(item)
+                .expect("Missing configuration"),
+            &ImageConfiguration::from_file(docker::tests::test_data_path("config.json"))
+                .expect("Config fixture")
+        );
+        assert_eq!(parsed_image.configurations().len(), 1);
+        assert_eq!(
+            parsed_image.repositories(),
+            &Some(
+                Repositories::from_file(docker::tests::test_data_path("repositories.json"))
+                    .expect("Repositories fixture")
+            )
+        );
+    }
+
+    #[test]
+    fn stats() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+
+        docker::tests::test_image_dir(dir.path());
+
+        let parsed_image = super::parse_image_dir(dir.path()).expect("Could not parse directory");
+
+        assert_eq!(
+            parsed_image.stats(),
+            ImageStats {
+                layer_count: 3,
+                tag_count: 1,
+                configuration_count: 1,
+                size: None,
+            }
+        );
+
+        let parsed_image = ParsedImage {
+            manifest: ImageManifest::from_file(docker::tests::test_data_path(
+                "manifest_layer_sources.json",
+            ))
+            .expect("Manifest fixture"),
+            ..parsed_image
+        };
+
+        assert_eq!(parsed_image.stats().layer_count(), &2);
+        assert_eq!(parsed_image.stats().size(), &Some(117256132));
+    }
+
+    #[test]
+    fn without_repositories() {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+
+        docker::tests::test_image_dir(dir.path());
+        fs::remove_file(dir.path().join(REPOSITORIES_FILE)).expect("Could not remove file");
+
+        let parsed_image = super::parse_image_dir(dir.path()).expect("Could not parse directory");
+
+        assert!(parsed_image.repositories().is_none());
+    }
+
+    #[test_case(MANIFEST_FILE, image::error::Error::MissingImageManifest; "Manifest")]
+    #[test_case(
+        docker::tests::CONFIG_PATH,
+        image::error::Error::MissingImageConfiguration;
+        "Configuration"
+    )]
+    fn missing_file(file: &str, expected: image::error::Error) {
+        let dir = tempfile::tempdir().expect("Could not create temporary directory");
+
+        docker::tests::test_image_dir(dir.path());
+        fs::remove_file(dir.path().join(file)).expect("Could not remove file");
+
+        let result = super::parse_image_dir(dir.path());
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::ImageError(ref err)))
+                    if err.to_string() == expected.to_string()
+            ),
+            "Missing {file} was not reported: {result:?}"
+        );
+    }
+}
@@ -0,0 +1,361 @@
+//! Image references in the `[registry/]repository[:tag][@digest]` form, as found in `RepoTags`
+//! and `repositories` files.
+
+use crate::docker;
+use crate::docker::digest::Digest;
+use crate::error::{ParsleyError, ParsleyResult};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Registry of the references without an explicit one.
+const DEFAULT_REGISTRY: &str = "docker.io";
+
+/// Namespace of the official images of the default registry.
+const DEFAULT_NAMESPACE: &str = "library";
+
+/// Tag of the references with neither a tag nor a digest.
+const DEFAULT_TAG: &str = "latest";
+
+/// Maximum length of a tag.
+const MAX_TAG_LEN: usize = 128;
+
+/// A normalized image reference.
+///
+/// Parsing fills in the defaults applied by Docker: references without a registry belong to
+/// `docker.io`, single component repositories of `docker.io` belong to the `library` namespace and
+/// references with neither a tag nor a digest are tagged `latest`. The fully qualified form is
+/// used when displaying a reference.
+///
+/// # Example
+/// ```
+/// use std::str::FromStr;
+/// use parsley::docker::reference::Reference;
+///
+/// let reference = Reference::from_str("postgres:15.4").unwrap();
+///
+/// assert_eq!(reference.registry(), "docker.io");
+/// assert_eq!(reference.repository(), "library/postgres");
+/// assert_eq!(reference.tag().as_deref(), Some("15.4"));
+/// assert_eq!(reference.to_string(), "docker.io/library/postgres:15.4");
+/// ```
+#[derive(Getters, Clone, Debug, Eq, Hash, PartialEq)]
+#[getset(get = "pub")]
+pub struct Reference {
+    /// Host, and optionally port, of the registry (e.g. `docker.io` or `localhost:5000`).
+    registry: String,
+    /// Path of the repository within the registry (e.g. `library/postgres`).
+    repository: String,
+    /// Tag of the image, if any.
+    tag: Option<String>,
+    /// Digest of the image manifest, if any.
+    digest: Option<Digest>,
+}
+
+impl Reference {
+    /// The registry and repository of the reference, without tag or digest (e.g.
+    /// `docker.io/library/postgres`).
+    pub fn name(&self) -> String {
+        format!("{}/{}", self.registry, self.repository)
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.registry, self.repository)?;
+
+        if let Some(tag) = &self.tag {
+            write!(f, ":{tag}")?;
+        }
+
+        if let Some(digest) = &self.digest {
+            write!(f, "@{digest}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Reference {
+    type Err = ParsleyError;
+
+    /// Attempts to parse a reference from the `[registry/]repository[:tag][@digest]` form.
+    ///
+    /// # Errors
+    ///
+    /// [ParsleyError::Docker](ParsleyError::Docker) if the reference is malformed.
+    ///
+    /// # Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use parsley::docker::reference::Reference;
+    ///
+    /// assert!(Reference::from_str("Postgres").is_err());
+    /// ```
+    fn from_str(s: &str) -> ParsleyResult<Self> {
+        let (name, digest) = match s.split_once('@') {
+            Some((name, digest)) => (
+                name,
+                Some(
+                    Digest::from_str(digest)
+                        .map_err(|err| invalid(s, &format!("malformed digest: {err}")))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        // A colon after the last slash separates the tag, while one before it separates the port
+        // of the registry
+        let (name, tag) = match name.rsplit_once(':') {
+            Some((name, tag)) if !tag.contains('/') => (name, Some(tag)),
+            _ => (name, None),
+        };
+        if let Some(tag) = tag {
+            validate_tag(tag).map_err(|reason| invalid(s, reason))?;
+        }
+
+        let (registry, repository) = split_name(name).map_err(|reason| invalid(s, reason))?;
+        let tag = match (tag, &digest) {
+            (None, None) => Some(DEFAULT_TAG.to_owned()),
+            (tag, _) => tag.map(str::to_owned),
+        };
+
+        Ok(Self {
+            registry,
+            repository,
+            tag,
+            digest,
+        })
+    }
+}
+
+impl Serialize for Reference {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Reference {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let reference: String = Deserialize::deserialize(deserializer)?;
+
+        Self::from_str(&reference).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Splits a repository name without tag or digest (e.g. `localhost:5000/postgres`) into its
+/// registry and repository path, applying the same defaults as [Reference](Reference).
+///
+/// # Errors
+/// [ParsleyError::Docker](ParsleyError::Docker) if the name is malformed or carries a tag or a
+/// digest.
+pub(crate) fn normalize_name(name: &str) -> ParsleyResult<(String, String)> {
+    let has_tag = name
+        .rsplit_once(':')
+        .map_or(false, |(_, tag)| !tag.contains('/'));
+
+    if has_tag || name.contains('@') {
+        return Err(invalid(name, "names cannot carry a tag or a digest"));
+    }
+
+    split_name(name).map_err(|reason| invalid(name, reason))
+}
+
+/// Splits a name into its registry, `docker.io` if none, and its validated repository path,
+/// within the `library` namespace for single component repositories of `docker.io`.
+fn split_name(name: &str) -> Result<(String, String), &'static str> {
+    let (registry, repository) = match name.split_once('/') {
+        Some((registry, repository)) if is_registry(registry) => (registry, repository),
+        _ => (DEFAULT_REGISTRY, name),
+    };
+
+    validate_repository(repository)?;
+
+    let repository = if registry == DEFAULT_REGISTRY && !repository.contains('/') {
+        format!("{DEFAULT_NAMESPACE}/{repository}")
+    } else {
+        repository.to_owned()
+    };
+
+    Ok((registry.to_owned(), repository))
+}
+
+/// Whether the first component of a name is a registry rather than part of the repository path.
+fn is_registry(component: &str) -> bool {
+    component.contains(['.', ':']) || component == "localhost"
+}
+
+/// Validates the path of a repository: `/` separated components made of lowercase alphanumerics,
+/// possibly joined by `.`, `_` or `-`.
+fn validate_repository(repository: &str) -> Result<(), &'static str> {
+    if repository.is_empty() {
+        return Err("missing repository");
+    }
+
+    for component in repository.split('/') {
+        let is_alphanumeric = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+
+        if !component.starts_with(is_alphanumeric) || !component.ends_with(is_alphanumeric) {
+            return Err("repository components must start and end with a lowercase alphanumeric");
+        }
+
+        if !component
+            .chars()
+            .all(|c| is_alphanumeric(c) || matches!(c, '.' | '_' | '-'))
+        {
+            return Err("repository components must be lowercase alphanumerics, '.', '_' or '-'");
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates a tag: up to 128 alphanumerics, `.`, `_` or `-`, not starting with `.` or `-`.
+fn validate_tag(tag: &str) -> Result<(), &'static str> {
+    if tag.is_empty() || tag.len() > MAX_TAG_LEN {
+        return Err("tag must have between 1 and 128 characters");
+    }
+
+    if tag.starts_with(['.', '-'])
+        || !tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    {
+        return Err(
+            "tag must be made of alphanumerics, '.', '_' or '-', not starting with '.' or '-'",
+        );
+    }
+
+    Ok(())
+}
+
+/// Creates the error reported for a malformed reference.
+fn invalid(reference: &str, reason: &str) -> ParsleyError {
+    docker::Error::InvalidReference {
+        reference: reference.to_owned(),
+        reason: reason.to_owned(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const DIGEST: &str = "sha256:1c3daa06574284614db07a23682ab6d1c344f09f8093ee10e5de4152a51677a1";
+
+    #[test_case("ubuntu", "docker.io", "library/ubuntu", Some("latest"), None; "Official image")]
+    #[test_case(
+        "library/postgres:15.4",
+        "docker.io",
+        "library/postgres",
+        Some("15.4"),
+        None;
+        "Namespaced tag"
+    )]
+    #[test_case(
+        &format!("host:5000/x@{DIGEST}"),
+        "host:5000",
+        "x",
+        None,
+        Some(DIGEST);
+        "Registry with port and digest"
+    )]
+    #[test_case(
+        &format!("localhost/team/app:v1.2@{DIGEST}"),
+        "localhost",
+        "team/app",
+        Some("v1.2"),
+        Some(DIGEST);
+        "Tag and digest"
+    )]
+    fn parse(s: &str, registry: &str, repository: &str, tag: Option<&str>, digest: Option<&str>) {
+        let reference = Reference::from_str(s).expect("Valid reference rejected");
+
+        assert_eq!(reference.registry(), registry);
+        assert_eq!(reference.repository(), repository);
+        assert_eq!(reference.tag().as_deref(), tag);
+        assert_eq!(
+            reference
+                .digest()
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+            digest
+        );
+        assert_eq!(
+            Reference::from_str(&reference.to_string()).expect("Displayed reference rejected"),
+            reference,
+            "Reference does not round trip"
+        );
+    }
+
+    #[test_case("ubuntu", "docker.io/library/ubuntu:latest"; "Official image")]
+    #[test_case("library/postgres:15.4", "docker.io/library/postgres:15.4"; "Namespaced tag")]
+    #[test_case(&format!("host:5000/x@{DIGEST}"), &format!("host:5000/x@{DIGEST}"); "Digest")]
+    fn display(s: &str, expected: &str) {
+        assert_eq!(
+            Reference::from_str(s)
+                .expect("Valid reference rejected")
+                .to_string(),
+            expected
+        );
+    }
+
+    #[test_case(""; "Empty")]
+    #[test_case("Postgres"; "Uppercase repository")]
+    #[test_case("postgres:"; "Empty tag")]
+    #[test_case("postgres:-15"; "Tag starting with dash")]
+    #[test_case("postgres@sha256:abc"; "Malformed digest")]
+    #[test_case("host:5000/"; "Missing repository")]
+    #[test_case("library//postgres"; "Empty component")]
+    fn malformed(s: &str) {
+        let result = Reference::from_str(s);
+
+        assert!(
+            matches!(
+                result,
+                Err(ParsleyError::Docker(docker::Error::InvalidReference { ref reference, .. })) if reference == s
+            ),
+            "Invalid reference was not rejected: {result:?}"
+        );
+    }
+
+    #[test_case("postgres", "docker.io", "library/postgres"; "Official image")]
+    #[test_case("localhost:5000/postgres", "localhost:5000", "postgres"; "Registry with port")]
+    fn normalize_name(name: &str, registry: &str, repository: &str) {
+        assert_eq!(
+            super::normalize_name(name).expect("Valid name rejected"),
+            (registry.to_owned(), repository.to_owned())
+        );
+    }
+
+    #[test_case("postgres:15.4"; "Tag")]
+    #[test_case(&format!("postgres@{DIGEST}"); "Digest")]
+    #[test_case("localhost:5000/"; "Missing repository")]
+    fn malformed_name(name: &str) {
+        assert!(
+            super::normalize_name(name).is_err(),
+            "Invalid name was not rejected"
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let reference = Reference::from_str("postgres:15.4").expect("Valid reference rejected");
+        let serialized = serde_json::to_string(&reference).expect("Failed to serialize");
+
+        assert_eq!(serialized, "\"docker.io/library/postgres:15.4\"");
+        assert_eq!(
+            serde_json::from_str::<Reference>(&serialized).expect("Failed to deserialize"),
+            reference
+        );
+    }
+}
@@ -19,6 +19,10 @@ pub enum ParsleyError {
     #[error("serde error: {0}")]
     SerDe(#[from] serde_json::Error),
 
+    /// Error caused by a content larger than the size limit it is read with
+    #[error("content exceeds the size limit of {0} bytes")]
+    SizeLimitExceeded(u64),
+
     /// Error caused by builders
     #[error("uninitialized field: {0}")]
     Builder(#[from] derive_builder::UninitializedFieldError),
@@ -27,7 +31,107 @@ pub enum ParsleyError {
     #[error("oci spec error: {0}")]
     OCI(#[from] oci_spec::OciSpecError),
 
+    /// Error caused by an OCI spec field that cannot be deserialized
+    #[error("oci spec error: invalid field {field}: {source}")]
+    OciWithContext {
+        /// Name of the invalid field
+        field: String,
+        /// Deserialization error of the field
+        #[source]
+        source: serde_json::Error,
+    },
+
     /// Error caused by Docker image
     #[error("docker image error: {0}")]
     Docker(#[from] docker::error::Error),
 }
+
+impl ParsleyError {
+    /// Whether the error stems from missing content: a file that does not exist or a part of an
+    /// image (e.g. its manifest, a configuration or a layer) that cannot be found.
+    ///
+    /// # Example
+    /// ``` no_run
+    /// use parsley::docker::image;
+    ///
+    /// match image::ImageManifest::from_file("manifest.json") {
+    ///     Ok(image_manifest) => println!("{} images", image_manifest.0.len()),
+    ///     Err(err) if err.is_not_found() => println!("not an unpacked docker save directory"),
+    ///     Err(err) => panic!("{err}"),
+    /// }
+    /// ```
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::Io(err) => err.kind() == io::ErrorKind::NotFound,
+            Self::Docker(docker::Error::ImageError(err)) => matches!(
+                err,
+                docker::image::error::Error::MissingImageManifest
+                    | docker::image::error::Error::MissingImageConfiguration
+                    | docker::image::error::Error::MissingLayer(_)
+            ),
+            Self::Docker(docker::Error::DistributionError(err)) => {
+                matches!(err, docker::distribution::error::Error::MissingRepositories)
+            }
+            Self::Docker(docker::Error::OciLayoutError(err)) => matches!(
+                err,
+                docker::oci_layout::error::Error::MissingLayoutHeader
+                    | docker::oci_layout::error::Error::MissingIndex
+                    | docker::oci_layout::error::Error::MissingBlob(_)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the error stems from content that cannot be serialized or deserialized, e.g.
+    /// malformed JSON or an OCI field of the wrong type.
+    pub fn is_serde(&self) -> bool {
+        matches!(self, Self::SerDe(_) | Self::OciWithContext { .. })
+    }
+
+    /// Whether the error stems from an IO operation, such as reading a file.
+    pub fn is_io(&self) -> bool {
+        matches!(self, Self::Io(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn serde_error() -> serde_json::Error {
+        serde_json::from_str::<serde_json::Value>("{").expect_err("Invalid JSON was accepted")
+    }
+
+    #[test_case(io::Error::from(io::ErrorKind::NotFound).into() => (true, false, true); "Missing file")]
+    #[test_case(io::Error::from(io::ErrorKind::PermissionDenied).into() => (false, false, true); "Unreadable file")]
+    #[test_case(serde_error().into() => (false, true, false); "Malformed JSON")]
+    #[test_case(
+        ParsleyError::OciWithContext { field: "os".to_owned(), source: serde_error() } => (false, true, false);
+        "Invalid OCI field"
+    )]
+    #[test_case(
+        docker::Error::from(docker::image::error::Error::MissingLayer("a/layer.tar".to_owned())).into()
+            => (true, false, false);
+        "Missing layer"
+    )]
+    #[test_case(
+        docker::Error::from(docker::distribution::error::Error::MissingRepositories).into()
+            => (true, false, false);
+        "Missing repositories"
+    )]
+    #[test_case(
+        docker::Error::from(docker::oci_layout::error::Error::MissingIndex).into()
+            => (true, false, false);
+        "Missing index"
+    )]
+    #[test_case(
+        docker::Error::from(docker::image::error::Error::InvalidImageManifest).into()
+            => (false, false, false);
+        "Invalid manifest"
+    )]
+    #[test_case(ParsleyError::SizeLimitExceeded(1) => (false, false, false); "Size limit exceeded")]
+    fn classify(error: ParsleyError) -> (bool, bool, bool) {
+        (error.is_not_found(), error.is_serde(), error.is_io())
+    }
+}
@@ -1,5 +1,7 @@
 pub mod docker;
 mod error;
+pub mod prelude;
 mod util;
 
 pub use crate::error::*;
+pub use crate::util::json::{merge, merge_with, try_merge, MergeConflict, MergeStrategy};
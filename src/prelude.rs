@@ -0,0 +1,50 @@
+//! Re-exports of the commonly used types, builders and functions, such that a single glob import
+//! covers most uses of the crate.
+//!
+//! # Example
+//! ```
+//! use parsley::prelude::*;
+//!
+//! fn build() -> ParsleyResult<ImageConfiguration> {
+//!     let health_check = HealthcheckConfigBuilder::default()
+//!         .test(vec!["CMD".to_owned(), "pg_isready".to_owned()])
+//!         .build()?;
+//!     let extension = ImageConfigurationExtensionBuilder::default()
+//!         .config(ConfigExtensionBuilder::default().health_check(health_check).build()?)
+//!         .build()?;
+//!     let item = ManifestItemBuilder::default()
+//!         .config("config.json")
+//!         .layers(vec!["3b05311756d9/layer.tar".to_owned()])
+//!         .build()?;
+//!     let manifest = ImageManifest::from_iter([item]);
+//!
+//!     assert_eq!(manifest.0[0].layer_count(), 1);
+//!
+//!     ImageConfigurationBuilder::default()
+//!         .oci_spec(oci_spec::image::ImageConfiguration::default())
+//!         .docker_oci_extension(extension)
+//!         .build()
+//! }
+//!
+//! let image_config: ImageConfiguration = build().unwrap();
+//! ```
+
+pub use crate::docker::archive::ArchiveWriter;
+#[cfg(feature = "fs")]
+pub use crate::docker::archive::{DockerArchive, EntryKind};
+pub use crate::docker::digest::{Algorithm, Digest};
+pub use crate::docker::distribution::{RegistryManifest, Repositories, Repository};
+pub use crate::docker::image::{
+    CommandLine, ConfigExtension, ConfigExtensionBuilder, DurationFormat, HealthcheckConfig,
+    HealthcheckConfigBuilder, ImageConfiguration, ImageConfigurationBuilder,
+    ImageConfigurationExtension, ImageConfigurationExtensionBuilder, ImageManifest, Layer,
+    ManifestItem, ManifestItemBuilder, ManifestSummary, MemoryLimit, Protocol,
+};
+pub use crate::docker::layer::Compression;
+pub use crate::docker::media_type::MediaType;
+#[cfg(feature = "fs")]
+pub use crate::docker::oci_layout::OciLayout;
+pub use crate::docker::reference::Reference;
+#[cfg(feature = "fs")]
+pub use crate::docker::{parse_image_dir, ImageStats, ParsedImage};
+pub use crate::error::{ParsleyError, ParsleyResult};
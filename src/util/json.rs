@@ -1,19 +1,71 @@
 //! Utility functions to help with JSON operations.
 
-use crate::error::ParsleyResult;
+use crate::error::{ParsleyError, ParsleyResult};
+use getset::Getters;
+#[cfg(feature = "fs")]
 use std::fs;
+use std::io::{Read, Write};
+#[cfg(feature = "fs")]
 use std::path::Path;
 use std::time::Duration;
+use thiserror::Error;
 
+/// Visitor of a duration as written by Docker, an integer count of nanoseconds, or as written by
+/// some other tools, a human readable string (e.g. `"1m30s"`).
+///
+/// Relying on the visitor callbacks only, rather than on a JSON representation, keeps the
+/// duration helpers usable with any serde backend.
+struct DurationVisitor;
+
+impl<'de> serde::de::Visitor<'de> for DurationVisitor {
+    type Value = Option<Duration>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("an integer count of nanoseconds or a human readable duration")
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, nanos: u64) -> Result<Self::Value, E> {
+        Ok(Some(Duration::from_nanos(nanos)))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, nanos: i64) -> Result<Self::Value, E> {
+        u64::try_from(nanos)
+            .map(|nanos| Some(Duration::from_nanos(nanos)))
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(nanos), &self))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        humantime::parse_duration(s)
+            .map(Some)
+            .map_err(|err| E::custom(format!("invalid duration {s:?}: {err}")))
+    }
+}
+
+/// Deserializes a duration given either as an integer count of nanoseconds or as a human readable
+/// string (e.g. `"30s"`).
 pub(crate) fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    Ok(Some(Duration::from_nanos(serde::Deserialize::deserialize(
-        deserializer,
-    )?)))
+    deserializer.deserialize_option(DurationVisitor)
 }
 
+/// Serializes a duration as an integer count of nanoseconds, as Docker does.
 pub(crate) fn serialize_duration<S>(
     duration: &Option<Duration>,
     serializer: S,
@@ -21,13 +73,64 @@ pub(crate) fn serialize_duration<S>(
 where
     S: serde::Serializer,
 {
-    duration.map_or(
-        Err(serde::ser::Error::custom("None value received")),
-        |duration| serializer.serialize_u64(duration.as_nanos() as u64),
-    )
+    match duration {
+        Some(duration) => {
+            let nanos = u64::try_from(duration.as_nanos()).map_err(|_| {
+                serde::ser::Error::custom(format!("duration {duration:?} overflows nanoseconds"))
+            })?;
+
+            serializer.serialize_some(&nanos)
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Strategy used when merging two JSON arrays.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MergeStrategy {
+    /// The array to be added replaces the existing one.
+    #[default]
+    Replace,
+    /// The array to be added is appended to the existing one.
+    Concat,
+    /// Elements at the same index are merged recursively, while the extra elements of the array
+    /// to be added are appended to the existing one.
+    ByIndex,
+}
+
+/// Recursively merges `json2` into `json1`, replacing arrays wholesale.
+///
+/// Null values of `json2` objects are skipped, thus keeping the existing values of `json1`.
+///
+/// # Example
+/// ```
+/// let mut json1 = serde_json::json!({"k1": "v1", "k2": [1]});
+/// parsley::merge(&mut json1, serde_json::json!({"k2": [2]}));
+///
+/// assert_eq!(json1, serde_json::json!({"k1": "v1", "k2": [2]}));
+/// ```
+pub fn merge(json1: &mut serde_json::Value, json2: serde_json::Value) {
+    merge_with(json1, json2, MergeStrategy::Replace)
 }
 
-pub(crate) fn merge(json1: &mut serde_json::Value, json2: serde_json::Value) {
+/// Recursively merges `json2` into `json1`, merging arrays according to `strategy`.
+///
+/// Null values of `json2` objects are skipped, thus keeping the existing values of `json1`.
+///
+/// # Example
+/// ```
+/// use parsley::MergeStrategy;
+///
+/// let mut json1 = serde_json::json!({"k1": [1, 2]});
+/// parsley::merge_with(&mut json1, serde_json::json!({"k1": [3]}), MergeStrategy::Concat);
+///
+/// assert_eq!(json1, serde_json::json!({"k1": [1, 2, 3]}));
+/// ```
+pub fn merge_with(
+    json1: &mut serde_json::Value,
+    json2: serde_json::Value,
+    strategy: MergeStrategy,
+) {
     match (json1, json2) {
         (
             current_level_json @ &mut serde_json::Value::Object(_),
@@ -40,16 +143,124 @@ pub(crate) fn merge(json1: &mut serde_json::Value, json2: serde_json::Value) {
                 .into_iter()
                 .filter(|(_, value)| *value != serde_json::Value::Null)
                 .for_each(|(key, value)| {
-                    merge(
+                    merge_with(
                         merged_map.entry(key).or_insert(serde_json::Value::Null),
                         value,
+                        strategy,
                     );
                 });
         }
+        (serde_json::Value::Array(current_array), serde_json::Value::Array(new_array))
+            if strategy == MergeStrategy::Concat =>
+        {
+            current_array.extend(new_array);
+        }
+        (serde_json::Value::Array(current_array), serde_json::Value::Array(new_array))
+            if strategy == MergeStrategy::ByIndex =>
+        {
+            for (index, value) in new_array.into_iter().enumerate() {
+                match current_array.get_mut(index) {
+                    Some(current_value) => merge_with(current_value, value, strategy),
+                    None => current_array.push(value),
+                }
+            }
+        }
         (a, b) => *a = b,
     }
 }
 
+/// Conflict reported by [try_merge](try_merge) when an object and a non object value meet at the
+/// same path.
+#[derive(Error, Getters, Clone, Debug, Eq, PartialEq)]
+#[error("cannot merge {new} into {existing} at {path}")]
+#[getset(get = "pub")]
+pub struct MergeConflict {
+    /// JSON pointer to the conflicting value (e.g. `/config/Env`).
+    path: String,
+    /// Type of the existing value.
+    existing: &'static str,
+    /// Type of the value to be added.
+    new: &'static str,
+}
+
+/// Recursively merges `json2` into `json1`, replacing arrays wholesale, like [merge](merge), but
+/// reports a conflict instead of overwriting when an object and a non object value meet at the
+/// same path.
+///
+/// Null values never conflict: null values of `json2` objects are skipped, while null values of
+/// `json1` are replaced. On conflict, `json1` is left partially merged.
+///
+/// # Errors
+/// [MergeConflict](MergeConflict) describing the first conflicting path and the types involved.
+///
+/// # Example
+/// ```
+/// let mut json1 = serde_json::json!({"config": {"User": "root"}});
+/// let conflict = parsley::try_merge(&mut json1, serde_json::json!({"config": "oops"})).unwrap_err();
+///
+/// assert_eq!(conflict.path(), "/config");
+/// ```
+pub fn try_merge(
+    json1: &mut serde_json::Value,
+    json2: serde_json::Value,
+) -> Result<(), MergeConflict> {
+    try_merge_at(json1, json2, &mut String::new())
+}
+
+fn try_merge_at(
+    json1: &mut serde_json::Value,
+    json2: serde_json::Value,
+    path: &mut String,
+) -> Result<(), MergeConflict> {
+    match (json1, json2) {
+        (serde_json::Value::Object(current_map), serde_json::Value::Object(new_map_content)) => {
+            for (key, value) in new_map_content
+                .into_iter()
+                .filter(|(_, value)| *value != serde_json::Value::Null)
+            {
+                let path_len = path.len();
+
+                // Escape the key as a JSON pointer reference token
+                path.push('/');
+                path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                try_merge_at(
+                    current_map.entry(key).or_insert(serde_json::Value::Null),
+                    value,
+                    path,
+                )?;
+                path.truncate(path_len);
+            }
+
+            Ok(())
+        }
+        (a, b) if !a.is_null() && !b.is_null() && (a.is_object() || b.is_object()) => {
+            Err(MergeConflict {
+                path: path.clone(),
+                existing: type_name(a),
+                new: type_name(&b),
+            })
+        }
+        (a, b) => {
+            *a = b;
+
+            Ok(())
+        }
+    }
+}
+
+/// Name of the JSON type of a value.
+fn type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+#[cfg(feature = "fs")]
 pub(crate) fn from_file<P, T>(path: P) -> ParsleyResult<T>
 where
     T: serde::de::DeserializeOwned,
@@ -58,7 +269,39 @@ where
     let path = path.as_ref();
     let manifest_file = std::io::BufReader::new(fs::File::open(path)?);
 
-    Ok(serde_json::from_reader(manifest_file)?)
+    from_reader(manifest_file)
+}
+
+pub(crate) fn from_reader<R, T>(reader: R) -> ParsleyResult<T>
+where
+    T: serde::de::DeserializeOwned,
+    R: Read,
+{
+    Ok(serde_json::from_reader(reader)?)
+}
+
+/// Deserializes at most `max_bytes` of a reader, failing without reading further past the limit.
+pub(crate) fn from_reader_limited<R, T>(reader: R, max_bytes: u64) -> ParsleyResult<T>
+where
+    T: serde::de::DeserializeOwned,
+    R: Read,
+{
+    from_slice(&read_limited(reader, max_bytes)?)
+}
+
+/// Reads at most `max_bytes` of a reader, failing without reading further past the limit.
+pub(crate) fn read_limited<R: Read>(reader: R, max_bytes: u64) -> ParsleyResult<Vec<u8>> {
+    let mut content = Vec::new();
+
+    reader
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut content)?;
+
+    if content.len() as u64 > max_bytes {
+        return Err(ParsleyError::SizeLimitExceeded(max_bytes));
+    }
+
+    Ok(content)
 }
 
 pub(crate) fn from_str<T>(s: &str) -> ParsleyResult<T>
@@ -75,6 +318,59 @@ where
     Ok(serde_json::from_slice(v)?)
 }
 
+#[cfg(feature = "tokio")]
+pub(crate) async fn from_file_async<P, T>(path: P) -> ParsleyResult<T>
+where
+    T: serde::de::DeserializeOwned + Send + 'static,
+    P: AsRef<Path>,
+{
+    let content = tokio::fs::read(path).await?;
+
+    tokio::task::spawn_blocking(move || from_slice(&content))
+        .await
+        .map_err(std::io::Error::from)?
+}
+
+#[cfg(feature = "fs")]
+pub(crate) fn to_file<P, T>(value: &T, path: P) -> ParsleyResult<()>
+where
+    T: serde::Serialize,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let file = std::io::BufWriter::new(fs::File::create(path)?);
+
+    to_writer(value, file)
+}
+
+pub(crate) fn to_writer<W, T>(value: &T, mut writer: W) -> ParsleyResult<()>
+where
+    T: serde::Serialize,
+    W: Write,
+{
+    serde_json::to_writer(&mut writer, value)?;
+
+    Ok(writer.flush()?)
+}
+
+/// Serializes `value` into its canonical form: compact JSON with the keys of every object sorted.
+///
+/// Going through [serde_json::Value] sorts the keys regardless of the order in which `value`
+/// serializes its fields, since maps are backed by a `BTreeMap`.
+pub(crate) fn to_canonical_vec<T>(value: &T) -> ParsleyResult<Vec<u8>>
+where
+    T: serde::Serialize,
+{
+    Ok(serde_json::to_vec(&serde_json::to_value(value)?)?)
+}
+
+pub(crate) fn to_string_pretty<T>(value: &T) -> ParsleyResult<String>
+where
+    T: serde::Serialize,
+{
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,6 +408,18 @@ mod tests {
             }
         }"; "Nested"
     )]
+    #[test_case(
+        "{\
+            \"k1\": [1, 2]
+        }",
+        "{\
+            \"k1\": [3]
+        }",
+        "{\
+            \"k1\": [3]
+        }"
+        ; "Array"
+    )]
     fn merge_cases(s1: &str, s2: &str, expected: &str) {
         let mut v1 = serde_json::Value::from_str(s1).expect("Invalid s1");
         let v2 = serde_json::Value::from_str(s2).expect("Invalid s2");
@@ -121,4 +429,202 @@ mod tests {
 
         assert_eq!(v1, expected);
     }
+
+    #[test_case(
+        "{\
+            \"k1\": [1, 2]
+        }",
+        "{\
+            \"k1\": [3]
+        }",
+        MergeStrategy::Replace,
+        "{\
+            \"k1\": [3]
+        }"
+        ; "Replace"
+    )]
+    #[test_case(
+        "{\
+            \"k1\": [1, 2]
+        }",
+        "{\
+            \"k1\": [3]
+        }",
+        MergeStrategy::Concat,
+        "{\
+            \"k1\": [1, 2, 3]
+        }"
+        ; "Concat"
+    )]
+    #[test_case(
+        "{\
+            \"k1\": [{\"k2\": \"v2\"}, 2]
+        }",
+        "{\
+            \"k1\": [{\"k3\": \"v3\"}, 4, 5]
+        }",
+        MergeStrategy::ByIndex,
+        "{\
+            \"k1\": [{\"k2\": \"v2\", \"k3\": \"v3\"}, 4, 5]
+        }"
+        ; "By index"
+    )]
+    #[test_case(
+        "{\
+            \"k1\": {\
+                \"k2\": [1]
+            }
+        }",
+        "{\
+            \"k1\": {\
+                \"k2\": [2]
+            }
+        }",
+        MergeStrategy::Concat,
+        "{\
+            \"k1\": {\
+                \"k2\": [1, 2]
+            }
+        }"
+        ; "Nested concat"
+    )]
+    fn merge_with_cases(s1: &str, s2: &str, strategy: MergeStrategy, expected: &str) {
+        let mut v1 = serde_json::Value::from_str(s1).expect("Invalid s1");
+        let v2 = serde_json::Value::from_str(s2).expect("Invalid s2");
+        let expected = serde_json::Value::from_str(expected).expect("Invalid expected");
+
+        merge_with(&mut v1, v2, strategy);
+
+        assert_eq!(v1, expected);
+    }
+
+    #[test_case(
+        "{\
+            \"config\": {\"User\": \"root\"}
+        }",
+        "{\
+            \"config\": {\"Env\": [\"A=B\"]}
+        }",
+        Ok("{\
+            \"config\": {\"User\": \"root\", \"Env\": [\"A=B\"]}
+        }")
+        ; "No conflict"
+    )]
+    #[test_case(
+        "{\
+            \"config\": {\"User\": \"root\"}
+        }",
+        "{\
+            \"config\": \"oops\"
+        }",
+        Err(("/config", "object", "string"))
+        ; "Object and scalar"
+    )]
+    #[test_case(
+        "{\
+            \"config\": {\"Env\": [\"A=B\"]}
+        }",
+        "{\
+            \"config\": {\"Env\": {\"A\": \"B\"}}
+        }",
+        Err(("/config/Env", "array", "object"))
+        ; "Nested array and object"
+    )]
+    #[test_case(
+        "{\
+            \"config\": null
+        }",
+        "{\
+            \"config\": {\"User\": \"root\"}
+        }",
+        Ok("{\
+            \"config\": {\"User\": \"root\"}
+        }")
+        ; "Null"
+    )]
+    fn try_merge_cases(
+        s1: &str,
+        s2: &str,
+        expected: Result<&str, (&str, &'static str, &'static str)>,
+    ) {
+        let mut v1 = serde_json::Value::from_str(s1).expect("Invalid s1");
+        let v2 = serde_json::Value::from_str(s2).expect("Invalid s2");
+        let result = try_merge(&mut v1, v2);
+
+        match expected {
+            Ok(expected) => {
+                assert_eq!(result, Ok(()));
+                assert_eq!(
+                    v1,
+                    serde_json::Value::from_str(expected).expect("Invalid expected")
+                );
+            }
+            Err((path, existing, new)) => assert_eq!(
+                result,
+                Err(MergeConflict {
+                    path: path.to_owned(),
+                    existing,
+                    new,
+                })
+            ),
+        }
+    }
+
+    #[test]
+    fn to_canonical_vec() {
+        #[derive(serde::Serialize)]
+        struct Unsorted {
+            k2: u8,
+            k1: serde_json::Value,
+        }
+
+        let value = Unsorted {
+            k2: 2,
+            k1: serde_json::json!({"k4": [4], "k3": "v3"}),
+        };
+
+        assert_eq!(
+            super::to_canonical_vec(&value).expect("Failed to serialize"),
+            b"{\"k1\":{\"k3\":\"v3\",\"k4\":[4]},\"k2\":2}"
+        );
+    }
+
+    #[test_case(serde_json::json!(null) => Some(None); "Null")]
+    #[test_case(serde_json::json!(90000000000_u64) => Some(Some(Duration::from_secs(90))); "Nanoseconds")]
+    #[test_case(serde_json::json!("30s") => Some(Some(Duration::from_secs(30))); "Seconds string")]
+    #[test_case(serde_json::json!("1m30s") => Some(Some(Duration::from_secs(90))); "Composite string")]
+    #[test_case(serde_json::json!("soon") => None; "Invalid string")]
+    #[test_case(serde_json::json!(-1) => None; "Negative")]
+    fn deserialize_duration_cases(value: serde_json::Value) -> Option<Option<Duration>> {
+        deserialize_duration(value).ok()
+    }
+
+    #[test_case(b"[1, 2]", 6 => Some(vec![1, 2]); "Exactly at limit")]
+    #[test_case(b"[1, 2]", 64 => Some(vec![1, 2]); "Under limit")]
+    #[test_case(b"[1, 2]", 5 => None; "Over limit")]
+    fn from_reader_limited_cases(content: &[u8], max_bytes: u64) -> Option<Vec<u32>> {
+        match from_reader_limited(content, max_bytes) {
+            Ok(value) => Some(value),
+            Err(ParsleyError::SizeLimitExceeded(limit)) if limit == max_bytes => None,
+            Err(err) => panic!("Unexpected error: {err}"),
+        }
+    }
+
+    #[test_case(None, serde_json::Value::Null; "None")]
+    #[test_case(
+        Some(Duration::from_nanos(10000000000)),
+        serde_json::Value::from(10000000000_u64);
+        "Some"
+    )]
+    fn serialize_duration_cases(duration: Option<Duration>, expected: serde_json::Value) {
+        let serialized = serialize_duration(&duration, serde_json::value::Serializer)
+            .expect("Failed to serialize duration");
+
+        assert_eq!(serialized, expected);
+    }
+
+    #[test]
+    fn serialize_duration_overflow() {
+        assert!(serialize_duration(&Some(Duration::MAX), serde_json::value::Serializer).is_err());
+    }
 }
@@ -1,3 +1,5 @@
 //! This modules implements different utility functions.
 
 pub(crate) mod json;
+#[cfg(feature = "fs")]
+pub(crate) mod path;
@@ -0,0 +1,38 @@
+//! Utility functions to help with paths referenced within image archives.
+
+use std::path::{Path, PathBuf};
+
+/// Joins `archive_path`, a path relative to the root of an archive as written within manifests
+/// (e.g. `<id>/layer.tar`), under `base_dir`, converting its forward slashes to the separator of
+/// the current platform.
+///
+/// Empty and `.` components of `archive_path` are skipped.
+pub(crate) fn join_archive_path<P: AsRef<Path>>(base_dir: P, archive_path: &str) -> PathBuf {
+    archive_path
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .fold(base_dir.as_ref().to_path_buf(), |path, component| {
+            path.join(component)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::MAIN_SEPARATOR;
+    use test_case::test_case;
+
+    #[test_case("base", "abc/layer.tar"; "Relative path")]
+    #[test_case("base", "./abc/layer.tar"; "Current directory prefix")]
+    #[test_case("base", "abc//layer.tar"; "Repeated separator")]
+    #[test_case("base/", "abc/layer.tar"; "Base with forward slash")]
+    #[test_case(&format!("base{MAIN_SEPARATOR}"), "abc/layer.tar"; "Base with platform separator")]
+    fn join_archive_path(base_dir: &str, archive_path: &str) {
+        let expected_path = Path::new("base").join("abc").join("layer.tar");
+
+        assert_eq!(
+            super::join_archive_path(base_dir, archive_path),
+            expected_path
+        );
+    }
+}